@@ -0,0 +1,56 @@
+//! Rough throughput numbers for the bulk transforms. Run with
+//! `cargo bench --bench transform`.
+
+use csr::{Caesar, CaesarTable};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const SIZE: usize = 16 * 1024 * 1024;
+const ROUNDS: u32 = 10;
+
+fn bench<F: FnMut()>(name: &str, mut f: F) {
+    // warm up once so the first round doesn't pay for page faults
+    f();
+
+    let mut best = Duration::MAX;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        f();
+        best = best.min(start.elapsed());
+    }
+
+    let mib_per_sec = SIZE as f64 / (1024.0 * 1024.0) / best.as_secs_f64();
+    println!("{:<20} {:>10.2?} {:>10.1} MiB/s", name, best, mib_per_sec);
+}
+
+fn main() {
+    let text: String = "The quick brown fox jumps over the lazy dog! 0123456789 "
+        .chars()
+        .cycle()
+        .take(SIZE)
+        .collect();
+    let caesar = Caesar::new(13);
+
+    bench("encrypt", || {
+        black_box(caesar.encrypt(black_box(&text)));
+    });
+
+    bench("decrypt", || {
+        black_box(caesar.decrypt(black_box(&text)));
+    });
+
+    let mut owned = text.clone();
+    bench("encrypt_in_place", || {
+        caesar.encrypt_in_place(black_box(&mut owned));
+    });
+
+    let mut bytes = text.clone().into_bytes();
+    bench("encrypt_bytes", || {
+        caesar.encrypt_bytes(black_box(&mut bytes));
+    });
+
+    let table = CaesarTable::new(caesar);
+    bench("table encrypt_bytes", || {
+        table.encrypt_bytes(black_box(&mut bytes));
+    });
+}
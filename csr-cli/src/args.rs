@@ -0,0 +1,345 @@
+use std::fmt;
+use std::path::PathBuf;
+
+/// The subcommand to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Encrypt,
+    Decrypt,
+    EncryptDir,
+    DecryptDir,
+    Crack,
+    Repl,
+    Serve,
+    Table,
+    Help,
+}
+
+impl Command {
+    /// The name the command is given on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            Command::Encrypt => "encrypt",
+            Command::Decrypt => "decrypt",
+            Command::EncryptDir => "encrypt-dir",
+            Command::DecryptDir => "decrypt-dir",
+            Command::Crack => "crack",
+            Command::Repl => "repl",
+            Command::Serve => "serve",
+            Command::Table => "table",
+            Command::Help => "help",
+        }
+    }
+}
+
+/// Parsed command line arguments.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Args {
+    pub command: Command,
+    pub key: Option<String>,
+    pub key_file: Option<PathBuf>,
+    pub top: Option<usize>,
+    pub json: bool,
+    pub explain: bool,
+    pub output: Option<PathBuf>,
+    pub progress: bool,
+    pub names: bool,
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+    pub dry_run: bool,
+    pub listen: Option<String>,
+    pub forward: Option<String>,
+    pub encrypt_only: bool,
+    pub files: Vec<PathBuf>,
+}
+
+/// A problem with the command line, reported along with the usage text.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UsageError(String);
+
+impl fmt::Display for UsageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl Args {
+    /// Parses arguments, not including the program name.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Args, UsageError> {
+        let mut args = args.into_iter();
+
+        let command = match args.next().as_deref() {
+            Some("encrypt") => Command::Encrypt,
+            Some("decrypt") => Command::Decrypt,
+            Some("encrypt-dir") => Command::EncryptDir,
+            Some("decrypt-dir") => Command::DecryptDir,
+            Some("crack") => Command::Crack,
+            Some("repl") => Command::Repl,
+            Some("serve") => Command::Serve,
+            Some("table") => Command::Table,
+            Some("help") | Some("-h") | Some("--help") | None => Command::Help,
+            Some(other) => return Err(UsageError(format!("unknown command '{}'", other))),
+        };
+
+        let mut parsed = Args {
+            command,
+            key: None,
+            key_file: None,
+            top: None,
+            json: false,
+            explain: false,
+            output: None,
+            progress: false,
+            names: false,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            dry_run: false,
+            listen: None,
+            forward: None,
+            encrypt_only: false,
+            files: Vec::new(),
+        };
+
+        while let Some(arg) = args.next() {
+            // everything after `--` is a file, even if it starts with `-`
+            if arg == "--" {
+                parsed.files.extend(args.by_ref().map(PathBuf::from));
+                break;
+            }
+
+            let (flag, inline) = match arg.find('=') {
+                Some(i) if arg.starts_with("--") => (&arg[..i], Some(arg[i + 1..].to_owned())),
+                _ => (&arg[..], None),
+            };
+
+            let mut value = |name: &str| {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| UsageError(format!("{} needs a value", name)))
+            };
+
+            match flag {
+                "-k" | "--key" => parsed.key = Some(value(flag)?),
+                "--key-file" => parsed.key_file = Some(PathBuf::from(value(flag)?)),
+                "-n" | "--top" => {
+                    let top = value(flag)?;
+                    let top = top
+                        .parse()
+                        .map_err(|_| UsageError(format!("invalid count '{}'", top)))?;
+                    parsed.top = Some(top);
+                }
+                "--json" => parsed.json = true,
+                "-e" | "--explain" => parsed.explain = true,
+                "-i" | "--input" => parsed.files.push(PathBuf::from(value(flag)?)),
+                "-o" | "--output" => parsed.output = Some(PathBuf::from(value(flag)?)),
+                "-p" | "--progress" => parsed.progress = true,
+                "--names" => parsed.names = true,
+                "--include" => parsed.include.push(value(flag)?),
+                "--exclude" => parsed.exclude.push(value(flag)?),
+                "--dry-run" => parsed.dry_run = true,
+                "-l" | "--listen" => parsed.listen = Some(value(flag)?),
+                "--forward" => parsed.forward = Some(value(flag)?),
+                "--encrypt-only" => parsed.encrypt_only = true,
+                "-h" | "--help" => parsed.command = Command::Help,
+                "-" => parsed.files.push(PathBuf::from(arg)),
+                _ if flag.starts_with('-') => {
+                    return Err(UsageError(format!("unknown option '{}'", flag)))
+                }
+                _ => parsed.files.push(PathBuf::from(arg)),
+            }
+        }
+
+        parsed.check()?;
+        Ok(parsed)
+    }
+
+    // rejects options the command would ignore, so mistakes in scripts
+    // fail loudly instead of being silently dropped
+    fn check(&self) -> Result<(), UsageError> {
+        use Command::*;
+
+        let command = self.command;
+        if command == Help {
+            return Ok(());
+        }
+
+        let transform = matches!(command, Encrypt | Decrypt);
+        let dir = matches!(command, EncryptDir | DecryptDir);
+        let plain_transform = transform && !self.explain;
+
+        let options = [
+            ("--key", self.key.is_some(), command != Crack),
+            ("--key-file", self.key_file.is_some(), command != Crack),
+            ("--top", self.top.is_some(), command == Crack),
+            (
+                "--json",
+                self.json,
+                command == Crack || transform && self.explain,
+            ),
+            ("--explain", self.explain, transform),
+            ("--output", self.output.is_some(), plain_transform),
+            ("--progress", self.progress, plain_transform),
+            ("--names", self.names, dir),
+            ("--include", !self.include.is_empty(), dir),
+            ("--exclude", !self.exclude.is_empty(), dir),
+            ("--dry-run", self.dry_run, dir),
+            ("--listen", self.listen.is_some(), command == Serve),
+            ("--forward", self.forward.is_some(), command == Serve),
+            (
+                "--encrypt-only",
+                self.encrypt_only,
+                command == Serve && self.forward.is_some(),
+            ),
+        ];
+
+        let name = if transform && self.explain {
+            format!("{} --explain", command.name())
+        } else {
+            command.name().to_string()
+        };
+
+        for (option, given, applies) in options.iter() {
+            if *given && !applies {
+                return Err(UsageError(format!("{} doesn't apply to {}", option, name)));
+            }
+        }
+
+        let takes_files = transform || dir || command == Crack;
+        if !self.files.is_empty() && !takes_files {
+            return Err(UsageError(format!("{} doesn't take files", name)));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, UsageError> {
+        Args::parse(args.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn key_forms() {
+        for args in [
+            &["encrypt", "-k", "-3"][..],
+            &["encrypt", "--key", "-3"],
+            &["encrypt", "--key=-3"],
+        ]
+        .iter()
+        {
+            let args = parse(args).unwrap();
+
+            assert_eq!(args.command, Command::Encrypt);
+            assert_eq!(args.key.as_deref(), Some("-3"));
+        }
+
+        let args = parse(&["encrypt", "--key-file", "secret.key"]).unwrap();
+        assert_eq!(args.key_file, Some(PathBuf::from("secret.key")));
+    }
+
+    #[test]
+    fn files_and_errors() {
+        let args = parse(&["decrypt", "a.txt", "-k", "3", "--", "-b.txt"]).unwrap();
+
+        assert_eq!(
+            args.files,
+            [PathBuf::from("a.txt"), PathBuf::from("-b.txt")]
+        );
+        assert!(parse(&["encrypt", "-k"]).is_err());
+        assert!(parse(&["encrypt", "--bogus"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+        assert_eq!(parse(&[]).unwrap().command, Command::Help);
+    }
+
+    #[test]
+    fn inapplicable_options() {
+        assert!(parse(&["encrypt", "-k", "3", "--json"]).is_err());
+        assert!(parse(&["encrypt", "-k", "3", "--names"]).is_err());
+        assert!(parse(&["encrypt", "-k", "3", "--listen", "x"]).is_err());
+        assert!(parse(&["encrypt", "-k", "3", "-e", "-o", "out.txt"]).is_err());
+        assert!(parse(&["crack", "-k", "3"]).is_err());
+        assert!(parse(&["serve", "-k", "3", "-l", "x", "file.txt"]).is_err());
+        assert!(parse(&["serve", "-k", "3", "-l", "x", "--encrypt-only"]).is_err());
+        assert!(parse(&["table", "-k", "3", "--top", "2"]).is_err());
+        assert!(parse(&["encrypt", "--names", "--help"]).is_ok());
+    }
+
+    #[test]
+    fn file_options() {
+        let args = parse(&[
+            "encrypt",
+            "-k",
+            "1",
+            "-i",
+            "in.txt",
+            "--output=out.txt",
+            "-p",
+        ])
+        .unwrap();
+
+        assert_eq!(args.files, [PathBuf::from("in.txt")]);
+        assert_eq!(args.output, Some(PathBuf::from("out.txt")));
+        assert!(args.progress);
+    }
+
+    #[test]
+    fn crack_options() {
+        let args = parse(&["crack", "--json", "-n", "5"]).unwrap();
+
+        assert_eq!(args.command, Command::Crack);
+        assert_eq!(args.top, Some(5));
+        assert!(args.json);
+        assert!(parse(&["crack", "--top", "many"]).is_err());
+    }
+
+    #[test]
+    fn explain_options() {
+        let args = parse(&["decrypt", "-k", "3", "-e", "--json"]).unwrap();
+
+        assert!(args.explain && args.json);
+    }
+
+    #[test]
+    fn dir_options() {
+        let args = parse(&[
+            "encrypt-dir",
+            "-k",
+            "3",
+            "--names",
+            "--include=*.csv",
+            "--exclude",
+            "tmp*",
+            "--dry-run",
+            "src",
+            "dst",
+        ])
+        .unwrap();
+
+        assert_eq!(args.command, Command::EncryptDir);
+        assert!(args.names && args.dry_run);
+        assert_eq!(args.include, ["*.csv"]);
+        assert_eq!(args.exclude, ["tmp*"]);
+        assert_eq!(args.files, [PathBuf::from("src"), PathBuf::from("dst")]);
+    }
+
+    #[test]
+    fn serve_options() {
+        let args = parse(&[
+            "serve",
+            "--listen",
+            "127.0.0.1:7000",
+            "--forward=example.com:80",
+            "--encrypt-only",
+        ])
+        .unwrap();
+
+        assert_eq!(args.command, Command::Serve);
+        assert_eq!(args.listen.as_deref(), Some("127.0.0.1:7000"));
+        assert_eq!(args.forward.as_deref(), Some("example.com:80"));
+        assert!(args.encrypt_only);
+    }
+}
@@ -0,0 +1,37 @@
+//! Just enough JSON output for the CLI's `--json` modes.
+
+use std::fmt::Write;
+
+/// Quotes and escapes a string as a JSON string literal.
+pub fn string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                write!(out, "\\u{:04x}", c as u32).unwrap();
+            }
+            c => out.push(c),
+        }
+    }
+
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes() {
+        assert_eq!(string("plain"), r#""plain""#);
+        assert_eq!(string("a \"b\"\n\\c\u{1}"), r#""a \"b\"\n\\c\u0001""#);
+    }
+}
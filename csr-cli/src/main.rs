@@ -0,0 +1,398 @@
+//! The `csr` command line tool, for using the caesar cipher from shell
+//! pipelines.
+
+mod args;
+mod json;
+mod progress;
+mod repl;
+mod serve;
+mod table;
+
+use args::{Args, Command};
+use csr::{Caesar, TreeOptions};
+use progress::Progress;
+use std::env;
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::Path;
+use std::process;
+
+// read for the key when neither -k nor --key-file is given
+const KEY_VAR: &str = "CSR_KEY";
+
+const USAGE: &str = "\
+usage: csr <command> [options] [FILE...]
+       csr encrypt-dir|decrypt-dir [options] SRC DST
+
+Reads each FILE in turn, or standard input if there are none (or for
+`-`), and writes the result to standard output. Input is streamed, so
+files larger than memory are fine.
+
+commands:
+    encrypt    encrypt the input
+    decrypt    decrypt the input
+    encrypt-dir
+               encrypt every file under the directory SRC into the
+               same layout under DST
+    decrypt-dir
+               decrypt every file under SRC into DST
+    crack      try every shift and print the most English-like
+               candidates, best first
+    repl       transform each line typed, changing the key with
+               commands like `:key 5` (see `:help`). starts with
+               rot13 unless a key is given
+    serve      accept TCP connections on the --listen address and
+               echo back what clients send, encrypted. with
+               --forward, relay to another address instead,
+               encrypting on the way there and decrypting replies
+    table      print which letter each letter encrypts to
+    help       print this help
+
+options:
+    -k, --key KEY        the shift to use: a number like 3 or -3, a rot
+                         name like rot13, or a letter like d
+        --key-file FILE  read the key from FILE, ignoring surrounding
+                         whitespace. without either, the CSR_KEY
+                         environment variable is used
+    -n, --top N          how many candidates crack prints (default 3)
+        --json           print crack candidates or an explanation as JSON
+    -e, --explain        with encrypt or decrypt, print what happens to
+                         each character instead of the result
+    -i, --input FILE     read FILE, the same as naming it after the options
+    -o, --output FILE    write to FILE instead of standard output
+    -p, --progress       show a progress bar on standard error
+        --names          with *-dir, transform file and directory names too
+        --include GLOB   with *-dir, only transform files matching GLOB,
+                         like '*.csv' (may be repeated)
+        --exclude GLOB   with *-dir, skip files matching GLOB
+        --dry-run        with *-dir, list what would be written and stop
+    -l, --listen ADDR    with serve, the address to listen on, like
+                         127.0.0.1:7000
+        --forward ADDR   with serve, relay connections to ADDR
+        --encrypt-only   with serve --forward, pass replies back undecrypted
+    -h, --help           print this help
+";
+
+fn main() {
+    let args = match Args::parse(std::env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("csr: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+
+    if let Err(e) = run(args) {
+        eprintln!("csr: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    match args.command {
+        Command::Help => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Command::Encrypt | Command::Decrypt if args.explain => explain(&args),
+        Command::Encrypt | Command::Decrypt => transform(&args),
+        Command::EncryptDir | Command::DecryptDir => tree(&args),
+        Command::Crack => crack(&args),
+        Command::Table => {
+            print!("{}", table::render(&key(&args)?));
+            Ok(())
+        }
+        Command::Repl => {
+            let caesar = optional_key(&args)?.unwrap_or_else(|| Caesar::new(13));
+            repl::run(caesar).map_err(Into::into)
+        }
+        Command::Serve => {
+            let listen = args
+                .listen
+                .as_deref()
+                .ok_or("an address is required (--listen ADDR)")?;
+            let relay = serve::Relay {
+                caesar: key(&args)?,
+                forward: args.forward.clone(),
+                encrypt_only: args.encrypt_only,
+            };
+            serve::run(listen, relay).map_err(|e| format!("{}: {}", listen, e).into())
+        }
+    }
+}
+
+fn transform(args: &Args) -> Result<(), Box<dyn Error>> {
+    let caesar = key(args)?;
+    let inputs = open_inputs(args)?;
+    let stdout = io::stdout();
+    let mut out: Box<dyn Write> = match &args.output {
+        Some(path) => {
+            // creating the output would truncate an input before it's read
+            if let Ok(output) = path.canonicalize() {
+                let clash = inputs.iter().any(|(file, reader)| {
+                    reader.is_some() && file.canonicalize().ok().as_ref() == Some(&output)
+                });
+                if clash {
+                    return Err(format!("{}: output is also an input", path.display()).into());
+                }
+            }
+
+            let file = File::create(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            Box::new(BufWriter::new(file))
+        }
+        None => Box::new(stdout.lock()),
+    };
+
+    let mut progress = if args.progress {
+        Some(Progress::new(total_size(args)))
+    } else {
+        None
+    };
+
+    read_inputs(inputs, |reader| {
+        let result = match progress.as_mut() {
+            Some(progress) => {
+                let start = progress.done();
+                stream(&caesar, args.command, reader, &mut out, |n| {
+                    progress.update(start + n)
+                })
+            }
+            None => stream(&caesar, args.command, reader, &mut out, |_| {}),
+        };
+        result.map(|_| ())
+    })?;
+
+    if let Some(progress) = progress.as_mut() {
+        progress.finish();
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn explain(args: &Args) -> Result<(), Box<dyn Error>> {
+    let caesar = key(args)?;
+    let mut input = String::new();
+    for_each_input(args, |reader| reader.read_to_string(&mut input).map(|_| ()))?;
+
+    let explanation = match args.command {
+        Command::Decrypt => caesar.explain_decrypt(&input),
+        _ => caesar.explain_encrypt(&input),
+    };
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    if args.json {
+        let steps: Vec<String> = explanation
+            .steps()
+            .iter()
+            .map(|step| {
+                format!(
+                    "{{\"original\":{},\"position\":{},\"shift\":{},\"result\":{}}}",
+                    json::string(&step.original.to_string()),
+                    step.position.map_or("null".into(), |p| p.to_string()),
+                    step.shift,
+                    json::string(&step.result.to_string())
+                )
+            })
+            .collect();
+        writeln!(out, "[{}]", steps.join(","))?;
+    } else {
+        write!(out, "{}", explanation)?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+fn tree(args: &Args) -> Result<(), Box<dyn Error>> {
+    let caesar = key(args)?;
+    let (src, dst) = match &args.files[..] {
+        [src, dst] => (src, dst),
+        _ => return Err("expected a source and a destination directory".into()),
+    };
+
+    let mut options = TreeOptions::new().names(args.names).dry_run(args.dry_run);
+    for glob in &args.include {
+        options = options.include(glob.as_str());
+    }
+    for glob in &args.exclude {
+        options = options.exclude(glob.as_str());
+    }
+
+    let entries = match args.command {
+        Command::DecryptDir => caesar.decrypt_tree(src, dst, &options),
+        _ => caesar.encrypt_tree(src, dst, &options),
+    }
+    .map_err(|e| format!("{}: {}", src.display(), e))?;
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    for entry in entries {
+        writeln!(out, "{} -> {}", entry.src.display(), entry.dst.display())?;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+// the combined size of the inputs, if they are all regular files
+fn total_size(args: &Args) -> Option<u64> {
+    if args.files.is_empty() {
+        return None;
+    }
+
+    args.files
+        .iter()
+        .map(|file| {
+            fs::metadata(file)
+                .ok()
+                .filter(|m| m.is_file())
+                .map(|m| m.len())
+        })
+        .sum()
+}
+
+fn crack(args: &Args) -> Result<(), Box<dyn Error>> {
+    let mut ciphertext = String::new();
+    for_each_input(args, |reader| {
+        reader.read_to_string(&mut ciphertext).map(|_| ())
+    })?;
+
+    let candidates = csr::crack(&ciphertext);
+    let top = &candidates[..args.top.unwrap_or(3).min(candidates.len())];
+
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    if args.json {
+        let entries: Vec<String> = top
+            .iter()
+            .map(|c| {
+                format!(
+                    "{{\"shift\":{},\"score\":{:.4},\"plaintext\":{}}}",
+                    c.caesar.shift(),
+                    c.score,
+                    json::string(&c.plaintext)
+                )
+            })
+            .collect();
+        writeln!(out, "[{}]", entries.join(","))?;
+    } else {
+        for (rank, c) in top.iter().enumerate() {
+            writeln!(
+                out,
+                "#{} shift {:>2}  score {:>10.2}",
+                rank + 1,
+                c.caesar.shift(),
+                c.score
+            )?;
+            writeln!(out, "{}", c.plaintext.trim_end())?;
+        }
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+// an input opened ahead of time, or None for stdin
+type Input<'a> = (&'a Path, Option<File>);
+
+// opens every input up front, so a missing one is reported before any
+// output has been created
+fn open_inputs(args: &Args) -> Result<Vec<Input<'_>>, Box<dyn Error>> {
+    let stdin = Path::new("-");
+    let files = if args.files.is_empty() {
+        vec![stdin]
+    } else {
+        args.files.iter().map(|f| f.as_path()).collect()
+    };
+
+    files
+        .into_iter()
+        .map(|file| {
+            if file == stdin {
+                return Ok((file, None));
+            }
+
+            File::open(file)
+                .map(|reader| (file, Some(reader)))
+                .map_err(|e| format!("{}: {}", file.display(), e).into())
+        })
+        .collect()
+}
+
+// calls f with a reader for each opened input in turn
+fn read_inputs<F>(inputs: Vec<Input>, mut f: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut(&mut dyn Read) -> io::Result<()>,
+{
+    for (file, reader) in inputs {
+        let result = match reader {
+            Some(mut reader) => f(&mut reader),
+            None => f(&mut io::stdin().lock()),
+        };
+
+        result.map_err(|e| format!("{}: {}", file.display(), e))?;
+    }
+
+    Ok(())
+}
+
+// calls f with a reader for each input file in turn, or for stdin
+fn for_each_input<F>(args: &Args, f: F) -> Result<(), Box<dyn Error>>
+where
+    F: FnMut(&mut dyn Read) -> io::Result<()>,
+{
+    read_inputs(open_inputs(args)?, f)
+}
+
+fn stream<R, W, P>(
+    caesar: &Caesar,
+    command: Command,
+    reader: R,
+    writer: W,
+    progress: P,
+) -> io::Result<u64>
+where
+    R: Read,
+    W: Write,
+    P: FnMut(u64),
+{
+    match command {
+        Command::Decrypt => caesar.decrypt_stream_with_progress(reader, writer, progress),
+        _ => caesar.encrypt_stream_with_progress(reader, writer, progress),
+    }
+}
+
+fn key(args: &Args) -> Result<Caesar, Box<dyn Error>> {
+    optional_key(args)?
+        .ok_or_else(|| format!("a key is required (-k KEY, --key-file FILE or {})", KEY_VAR).into())
+}
+
+// reads the key from -k, --key-file or the environment, in that order.
+// keys from files and the environment aren't echoed in errors, since the
+// point of them is to keep keys out of logs
+fn optional_key(args: &Args) -> Result<Option<Caesar>, Box<dyn Error>> {
+    let (text, source) = if let Some(key) = &args.key {
+        (key.clone(), format!("'{}'", key))
+    } else if let Some(path) = &args.key_file {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        (text, format!("in {}", path.display()))
+    } else {
+        match env::var(KEY_VAR) {
+            Ok(text) if !text.is_empty() => (text, format!("in {}", KEY_VAR)),
+            Err(env::VarError::NotUnicode(_)) => {
+                return Err(format!("{} is not valid UTF-8", KEY_VAR).into())
+            }
+            _ => return Ok(None),
+        }
+    };
+
+    text.trim()
+        .parse()
+        .map(Some)
+        .map_err(|e| format!("invalid key {}: {}", source, e).into())
+}
@@ -0,0 +1,116 @@
+//! A minimal progress bar on stderr, for long-running file jobs.
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+// redrawing more often than this just burns time on the terminal
+const REDRAW: Duration = Duration::from_millis(100);
+const WIDTH: usize = 30;
+
+/// Tracks bytes processed and draws them on stderr. The total is shown
+/// as a bar when known (regular files), and as a plain count otherwise.
+pub struct Progress {
+    total: Option<u64>,
+    done: u64,
+    drawn: Option<Instant>,
+}
+
+impl Progress {
+    pub fn new(total: Option<u64>) -> Self {
+        Progress {
+            total,
+            done: 0,
+            drawn: None,
+        }
+    }
+
+    /// Returns the bytes processed so far.
+    pub fn done(&self) -> u64 {
+        self.done
+    }
+
+    /// Records the bytes processed so far, redrawing if it's been a while.
+    pub fn update(&mut self, done: u64) {
+        self.done = done;
+
+        if self.drawn.map_or(true, |at| at.elapsed() >= REDRAW) {
+            self.draw();
+        }
+    }
+
+    fn draw(&mut self) {
+        self.drawn = Some(Instant::now());
+
+        let line = match self.total {
+            Some(total) if total > 0 => {
+                let fraction = (self.done as f64 / total as f64).min(1.0);
+                let filled = (fraction * WIDTH as f64) as usize;
+                format!(
+                    "[{}{}] {:>3}% {} / {}",
+                    "#".repeat(filled),
+                    " ".repeat(WIDTH - filled),
+                    (fraction * 100.0) as u32,
+                    human(self.done),
+                    human(total)
+                )
+            }
+            _ => human(self.done),
+        };
+
+        // progress is best effort, so a closed stderr isn't an error
+        let _ = write!(io::stderr(), "\r{}", line);
+    }
+
+    /// Draws the final state and moves to a new line.
+    pub fn finish(&mut self) {
+        self.draw();
+        let _ = writeln!(io::stderr());
+    }
+}
+
+// formats a byte count with a binary unit, like 1.5 GiB
+fn human(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn human_units() {
+        assert_eq!(human(12), "12 B");
+        assert_eq!(human(1536), "1.5 KiB");
+        assert_eq!(human(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+
+    #[test]
+    fn counts_streamed_bytes() {
+        let mut progress = Progress::new(Some(10));
+        // pretend we just drew, so the test doesn't write to stderr
+        progress.drawn = Some(Instant::now());
+
+        for input in [&b"hello"[..], b"world"].iter() {
+            let start = progress.done();
+            let mut out = Vec::new();
+            csr::Caesar::new(1)
+                .encrypt_stream_with_progress(*input, &mut out, |n| progress.update(start + n))
+                .unwrap();
+        }
+
+        assert_eq!(progress.done(), 10);
+    }
+}
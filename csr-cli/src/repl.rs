@@ -0,0 +1,143 @@
+//! `csr repl`: keeps a cipher in memory and transforms each line typed.
+
+use csr::Caesar;
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+Type a line to transform it. Lines starting with `:` are commands:
+    :key KEY     change the key (like 3, -3, rot13 or d)
+    :encrypt     encrypt the lines that follow
+    :decrypt     decrypt the lines that follow
+    :show        print the current key and mode
+    :help        print this help
+    :quit        exit (end of input works too)";
+
+/// The state of a REPL session.
+pub struct Repl {
+    caesar: Caesar,
+    decrypt: bool,
+}
+
+/// What the session wants done after a line.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Reply {
+    Print(String),
+    Quit,
+}
+
+impl Repl {
+    pub fn new(caesar: Caesar) -> Self {
+        Repl {
+            caesar,
+            decrypt: false,
+        }
+    }
+
+    /// Handles one line of input, without its line ending.
+    pub fn handle(&mut self, line: &str) -> Reply {
+        let command = match line.strip_prefix(':') {
+            Some(command) => command.trim(),
+            None => {
+                return Reply::Print(if self.decrypt {
+                    self.caesar.decrypt(line)
+                } else {
+                    self.caesar.encrypt(line)
+                })
+            }
+        };
+
+        let (name, arg) = match command.find(char::is_whitespace) {
+            Some(i) => (&command[..i], command[i..].trim()),
+            None => (command, ""),
+        };
+
+        Reply::Print(match name {
+            "key" | "k" => match arg.parse() {
+                Ok(caesar) => {
+                    self.caesar = caesar;
+                    self.show()
+                }
+                Err(e) => format!("invalid key '{}': {}", arg, e),
+            },
+            "encrypt" | "e" => {
+                self.decrypt = false;
+                self.show()
+            }
+            "decrypt" | "d" => {
+                self.decrypt = true;
+                self.show()
+            }
+            "show" => self.show(),
+            "help" | "h" | "?" => HELP.to_owned(),
+            "quit" | "q" | "exit" => return Reply::Quit,
+            _ => format!("unknown command ':{}', try :help", name),
+        })
+    }
+
+    fn show(&self) -> String {
+        let mode = if self.decrypt {
+            "decrypting"
+        } else {
+            "encrypting"
+        };
+        format!("{} with shift {}", mode, self.caesar.shift())
+    }
+}
+
+/// Runs a session over stdin and stdout. The prompt goes to stderr so that
+/// piped output stays clean.
+pub fn run(caesar: Caesar) -> io::Result<()> {
+    let mut repl = Repl::new(caesar);
+    let stdin = io::stdin();
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    writeln!(io::stderr(), "{}, :help for commands", repl.show())?;
+
+    let mut lines = stdin.lock().lines();
+    loop {
+        write!(io::stderr(), "> ")?;
+        io::stderr().flush()?;
+
+        let line = match lines.next() {
+            Some(line) => line?,
+            None => break,
+        };
+
+        match repl.handle(&line) {
+            Reply::Print(text) => writeln!(out, "{}", text)?,
+            Reply::Quit => break,
+        }
+        out.flush()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn print(s: &str) -> Reply {
+        Reply::Print(s.to_owned())
+    }
+
+    #[test]
+    fn session() {
+        let mut repl = Repl::new(Caesar::new(2));
+
+        assert_eq!(repl.handle("Hello world!"), print("Jgnnq yqtnf!"));
+        assert_eq!(repl.handle(":key rot13"), print("encrypting with shift 13"));
+        assert_eq!(repl.handle(":decrypt"), print("decrypting with shift 13"));
+        assert_eq!(repl.handle("Uryyb"), print("Hello"));
+        assert_eq!(
+            repl.handle(":key nope"),
+            print("invalid key 'nope': invalid cipher spec")
+        );
+        assert_eq!(
+            repl.handle(":bogus"),
+            print("unknown command ':bogus', try :help")
+        );
+        assert_eq!(repl.handle(":quit"), Reply::Quit);
+    }
+}
@@ -0,0 +1,179 @@
+//! `csr serve`: a TCP relay that passes traffic through the cipher.
+//!
+//! Without an upstream, each client gets back what it sends, encrypted.
+//! With one, data from the client is encrypted on its way upstream and
+//! replies are decrypted on their way back, unless the relay is
+//! encrypt-only, in which case replies pass through untouched.
+
+use csr::Caesar;
+use std::io;
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::thread;
+
+/// How connections are relayed.
+#[derive(Debug, Clone)]
+pub struct Relay {
+    pub caesar: Caesar,
+    pub forward: Option<String>,
+    pub encrypt_only: bool,
+}
+
+/// Accepts connections on `listen` forever, relaying each on its own
+/// thread. Problems with one connection are reported and don't stop the
+/// others.
+pub fn run(listen: &str, relay: Relay) -> io::Result<()> {
+    let listener = TcpListener::bind(listen)?;
+    eprintln!("csr: listening on {}", listener.local_addr()?);
+
+    for client in listener.incoming() {
+        let client = match client {
+            Ok(client) => client,
+            Err(e) => {
+                eprintln!("csr: accept: {}", e);
+                continue;
+            }
+        };
+
+        let relay = relay.clone();
+        thread::spawn(move || {
+            let peer = client
+                .peer_addr()
+                .map_or_else(|_| "unknown peer".into(), |addr| addr.to_string());
+
+            eprintln!("csr: {} connected", peer);
+            match relay.handle(client) {
+                Ok(()) => eprintln!("csr: {} disconnected", peer),
+                Err(e) => eprintln!("csr: {}: {}", peer, e),
+            }
+        });
+    }
+
+    Ok(())
+}
+
+impl Relay {
+    /// Relays one client connection until both directions are done.
+    pub fn handle(&self, client: TcpStream) -> io::Result<()> {
+        let upstream = match &self.forward {
+            Some(addr) => TcpStream::connect(addr)?,
+            // echo: the client is its own upstream
+            None => {
+                return self
+                    .pipe(client.try_clone()?, client, Pass::Encrypt)
+                    .map(|_| ())
+            }
+        };
+
+        let back = {
+            let relay = self.clone();
+            let (from, to) = (upstream.try_clone()?, client.try_clone()?);
+            let pass = if self.encrypt_only {
+                Pass::Plain
+            } else {
+                Pass::Decrypt
+            };
+
+            thread::spawn(move || relay.pipe(from, to, pass))
+        };
+
+        let there = self.pipe(client, upstream, Pass::Encrypt);
+        let back = back
+            .join()
+            .unwrap_or_else(|_| Err(io::Error::other("relay thread panicked")));
+
+        there.and(back).map(|_| ())
+    }
+
+    // copies one direction, then tells the receiving side there's nothing
+    // more to come
+    fn pipe(&self, from: TcpStream, to: TcpStream, pass: Pass) -> io::Result<u64> {
+        let result = match pass {
+            Pass::Encrypt => self.caesar.encrypt_stream(&from, &to),
+            Pass::Decrypt => self.caesar.decrypt_stream(&from, &to),
+            Pass::Plain => io::copy(&mut &from, &mut &to),
+        };
+
+        // the other side may already be gone, which is fine
+        let _ = to.shutdown(Shutdown::Write);
+        result
+    }
+}
+
+// what happens to data on its way through one direction of a relay
+#[derive(Debug, Clone, Copy)]
+enum Pass {
+    Encrypt,
+    Decrypt,
+    Plain,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    // starts a relay on a free port that serves a single connection
+    fn serve_once(relay: Relay) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+
+        thread::spawn(move || {
+            let (client, _) = listener.accept().unwrap();
+            relay.handle(client).unwrap();
+        });
+
+        addr
+    }
+
+    fn exchange(addr: &str, message: &str) -> String {
+        let mut stream = TcpStream::connect(addr).unwrap();
+        stream.write_all(message.as_bytes()).unwrap();
+        stream.shutdown(Shutdown::Write).unwrap();
+
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply).unwrap();
+        reply
+    }
+
+    #[test]
+    fn echo() {
+        let addr = serve_once(Relay {
+            caesar: Caesar::new(1),
+            forward: None,
+            encrypt_only: false,
+        });
+
+        assert_eq!(exchange(&addr, "Hal"), "Ibm");
+    }
+
+    #[test]
+    fn forward_both_ways() {
+        let caesar = Caesar::new(13);
+
+        // an upstream that replies with whatever it received, verbatim
+        let upstream = serve_once(Relay {
+            caesar: Caesar::new(0),
+            forward: None,
+            encrypt_only: false,
+        });
+
+        let relay = serve_once(Relay {
+            caesar,
+            forward: Some(upstream.clone()),
+            encrypt_only: false,
+        });
+        assert_eq!(exchange(&relay, "Hello"), "Hello");
+
+        let upstream = serve_once(Relay {
+            caesar: Caesar::new(0),
+            forward: None,
+            encrypt_only: false,
+        });
+        let relay = serve_once(Relay {
+            caesar,
+            forward: Some(upstream),
+            encrypt_only: true,
+        });
+        assert_eq!(exchange(&relay, "Hello"), "Uryyb");
+    }
+}
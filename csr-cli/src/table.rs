@@ -0,0 +1,39 @@
+//! `csr table`: prints the substitution alphabet for a key.
+
+use csr::Caesar;
+
+/// Formats the plaintext and ciphertext alphabets as two aligned rows.
+pub fn render(caesar: &Caesar) -> String {
+    let row = |label: &str, letters: &[char]| {
+        let letters: Vec<String> = letters.iter().map(char::to_string).collect();
+        format!("{:<8}{}\n", label, letters.join(" "))
+    };
+
+    let plain: Vec<char> = ('a'..='z').collect();
+
+    format!(
+        "{}{}",
+        row("plain", &plain),
+        row("cipher", &caesar.mapping())
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_line_up() {
+        let table = render(&Caesar::new(5));
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(
+            lines[0],
+            "plain   a b c d e f g h i j k l m n o p q r s t u v w x y z"
+        );
+        assert_eq!(
+            lines[1],
+            "cipher  f g h i j k l m n o p q r s t u v w x y z a b c d e"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Derive macros for the [csr](https://docs.rs/csr) crate. These are
+//! re-exported by csr behind its `derive` feature, so depend on csr rather
+//! than on this crate directly.
+
+use proc_macro::{Delimiter, Group, Spacing, TokenStream, TokenTree};
+
+/// Derives `csr::Obfuscate` for a struct, encrypting or decrypting every
+/// `String` field in place. Fields marked `#[csr(skip)]` are left alone,
+/// as are fields of any other type. Any other `csr` attribute is an error,
+/// so a typo can't quietly expose a field meant to be skipped.
+///
+/// Only structs without generic parameters are supported.
+#[proc_macro_derive(CaesarObfuscate, attributes(csr))]
+pub fn derive_caesar_obfuscate(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(output) => output,
+        Err(message) => format!("compile_error!({:?});", message).parse().unwrap(),
+    }
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, String> {
+    let mut tokens = input.into_iter().peekable();
+
+    // skip outer attributes and visibility to get to the struct keyword
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(ref p)) if p.as_char() == '#' => {
+                tokens.next();
+            }
+            Some(TokenTree::Ident(ref i)) if i.to_string() == "pub" => {
+                if let Some(TokenTree::Group(g)) = tokens.peek() {
+                    if g.delimiter() == Delimiter::Parenthesis {
+                        tokens.next();
+                    }
+                }
+            }
+            Some(TokenTree::Ident(ref i)) if i.to_string() == "struct" => break,
+            _ => return Err("CaesarObfuscate can only be derived for structs".into()),
+        }
+    }
+
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name.to_string(),
+        _ => return Err("expected a struct name".into()),
+    };
+
+    let fields = match tokens.next() {
+        Some(TokenTree::Group(ref g)) if g.delimiter() == Delimiter::Brace => named_fields(g)?,
+        Some(TokenTree::Group(ref g)) if g.delimiter() == Delimiter::Parenthesis => {
+            tuple_fields(g)?
+        }
+        Some(TokenTree::Punct(ref p)) if p.as_char() == ';' => Vec::new(),
+        Some(TokenTree::Punct(ref p)) if p.as_char() == '<' => {
+            return Err("CaesarObfuscate does not support generic structs".into())
+        }
+        _ => return Err("expected struct fields".into()),
+    };
+
+    let body = |method: &str| {
+        fields
+            .iter()
+            .filter(|field| field.is_string && !field.skip)
+            .map(|field| format!("caesar.{}_in_place(&mut self.{});", method, field.name))
+            .collect::<String>()
+    };
+
+    let output = format!(
+        "impl ::csr::Obfuscate for {name} {{
+            fn obfuscate(&mut self, caesar: &::csr::Caesar) {{ {encrypt} }}
+            fn deobfuscate(&mut self, caesar: &::csr::Caesar) {{ {decrypt} }}
+        }}",
+        name = name,
+        encrypt = body("encrypt"),
+        decrypt = body("decrypt"),
+    );
+
+    output.parse().map_err(|_| "failed to generate impl".into())
+}
+
+struct Field {
+    name: String,
+    is_string: bool,
+    skip: bool,
+}
+
+fn named_fields(group: &Group) -> Result<Vec<Field>, String> {
+    let mut fields = Vec::new();
+
+    for tokens in split_fields(group) {
+        let (skip, rest) = strip_attributes(&tokens)?;
+        let colon = rest
+            .iter()
+            .position(|t| matches!(t, TokenTree::Punct(p) if p.as_char() == ':'));
+
+        if let Some(colon) = colon {
+            fields.push(Field {
+                name: rest[colon - 1].to_string(),
+                is_string: is_string(&rest[colon + 1..]),
+                skip,
+            });
+        }
+    }
+
+    Ok(fields)
+}
+
+fn tuple_fields(group: &Group) -> Result<Vec<Field>, String> {
+    split_fields(group)
+        .into_iter()
+        .enumerate()
+        .map(|(index, tokens)| {
+            let (skip, rest) = strip_attributes(&tokens)?;
+            let ty = strip_visibility(rest);
+
+            Ok(Field {
+                name: index.to_string(),
+                is_string: is_string(ty),
+                skip,
+            })
+        })
+        .collect()
+}
+
+// splits a field list on top-level commas, keeping track of angle brackets
+// since they aren't token groups
+fn split_fields(group: &Group) -> Vec<Vec<TokenTree>> {
+    let mut fields = vec![Vec::new()];
+    let mut depth = 0usize;
+    let mut after_dash = false;
+
+    for token in group.stream() {
+        if let TokenTree::Punct(ref p) = token {
+            match p.as_char() {
+                '<' => depth += 1,
+                // the `>` in `->` doesn't close anything
+                '>' if !after_dash => depth = depth.saturating_sub(1),
+                ',' if depth == 0 => {
+                    fields.push(Vec::new());
+                    continue;
+                }
+                _ => {}
+            }
+
+            after_dash = p.as_char() == '-' && p.spacing() == Spacing::Joint;
+        } else {
+            after_dash = false;
+        }
+
+        fields.last_mut().unwrap().push(token);
+    }
+
+    fields.retain(|field| !field.is_empty());
+    fields
+}
+
+// removes leading attributes, returning whether one of them was
+// `#[csr(skip)]`. other `csr` attributes are rejected
+fn strip_attributes(tokens: &[TokenTree]) -> Result<(bool, &[TokenTree]), String> {
+    let mut skip = false;
+    let mut rest = tokens;
+
+    while let [TokenTree::Punct(p), TokenTree::Group(g), tail @ ..] = rest {
+        if p.as_char() != '#' {
+            break;
+        }
+
+        let attr: Vec<TokenTree> = g.stream().into_iter().collect();
+        match &attr[..] {
+            [TokenTree::Ident(name), rest @ ..] if name.to_string() == "csr" => {
+                let args = match rest {
+                    [TokenTree::Group(args)] if args.delimiter() == Delimiter::Parenthesis => {
+                        args.stream().to_string()
+                    }
+                    _ => String::new(),
+                };
+
+                if args.trim() != "skip" {
+                    return Err(format!(
+                        "unknown csr attribute `#[csr({})]`, expected `#[csr(skip)]`",
+                        args.trim()
+                    ));
+                }
+
+                skip = true;
+            }
+            _ => {}
+        }
+
+        rest = tail;
+    }
+
+    Ok((skip, rest))
+}
+
+fn strip_visibility(tokens: &[TokenTree]) -> &[TokenTree] {
+    match tokens {
+        [TokenTree::Ident(i), TokenTree::Group(g), rest @ ..]
+            if i.to_string() == "pub" && g.delimiter() == Delimiter::Parenthesis =>
+        {
+            rest
+        }
+        [TokenTree::Ident(i), rest @ ..] if i.to_string() == "pub" => rest,
+        _ => tokens,
+    }
+}
+
+// accepts `String` and paths ending in it, like `std::string::String`
+fn is_string(ty: &[TokenTree]) -> bool {
+    let path_only = ty.iter().all(|t| match t {
+        TokenTree::Ident(_) => true,
+        TokenTree::Punct(p) => p.as_char() == ':',
+        _ => false,
+    });
+
+    path_only && matches!(ty.last(), Some(TokenTree::Ident(i)) if i.to_string() == "String")
+}
@@ -0,0 +1,44 @@
+/*
+ * C bindings for csr, a caesar cipher library. Link against the
+ * libcsr_ffi built from the csr-ffi crate.
+ *
+ * Keep this in sync with csr-ffi/src/lib.rs.
+ */
+
+#ifndef CSR_H
+#define CSR_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* A caesar cipher. Passed by value; there is nothing to free. */
+typedef struct CsrCaesar {
+    /* between 0 and 25 when made by one of the constructors */
+    uint8_t shift;
+} CsrCaesar;
+
+/* Constructs a cipher, reducing shift modulo 26. Negative shifts count
+ * backwards. */
+CsrCaesar csr_caesar_new(int64_t shift);
+
+/* Parses a spec like "13", "rot13", "-3" or "d" into out. Returns 0 on
+ * success, and -1 if the spec is invalid or a pointer is NULL. */
+int csr_caesar_parse(const char *spec, CsrCaesar *out);
+
+/* Returns the cipher that undoes caesar. */
+CsrCaesar csr_caesar_inverse(CsrCaesar caesar);
+
+/* Encrypts or decrypts len bytes at buf in place. Only ASCII letters
+ * change, so UTF-8 stays valid. Does nothing if buf is NULL. */
+void csr_encrypt_buf(CsrCaesar caesar, uint8_t *buf, size_t len);
+void csr_decrypt_buf(CsrCaesar caesar, uint8_t *buf, size_t len);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* CSR_H */
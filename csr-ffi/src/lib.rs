@@ -0,0 +1,133 @@
+//! C bindings for [csr](https://docs.rs/csr). Building this crate produces
+//! `libcsr_ffi.so`/`libcsr_ffi.a` (or the platform equivalents), to be used with
+//! the header in `include/csr.h`.
+//!
+//! A cipher is a small value type, [`CsrCaesar`], passed by value, so
+//! there is nothing to allocate or free.
+
+use csr::Caesar;
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::slice;
+
+/// A Caesar cipher. `shift` is always between 0 and 25 when the value
+/// comes from one of the constructors.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CsrCaesar {
+    pub shift: u8,
+}
+
+impl From<Caesar> for CsrCaesar {
+    fn from(caesar: Caesar) -> Self {
+        CsrCaesar {
+            shift: caesar.shift(),
+        }
+    }
+}
+
+impl From<CsrCaesar> for Caesar {
+    fn from(caesar: CsrCaesar) -> Self {
+        Caesar::new(caesar.shift)
+    }
+}
+
+/// Constructs a cipher, reducing `shift` modulo 26. Negative shifts count
+/// backwards.
+#[no_mangle]
+pub extern "C" fn csr_caesar_new(shift: i64) -> CsrCaesar {
+    Caesar::new(shift).into()
+}
+
+/// Parses a cipher spec like `"13"`, `"rot13"`, `"-3"` or `"d"` into
+/// `out`. Returns 0 on success and -1 if the spec is invalid or either
+/// pointer is null, leaving `out` untouched.
+///
+/// # Safety
+///
+/// `spec` must be null or a valid NUL-terminated string, and `out` must be
+/// null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn csr_caesar_parse(spec: *const c_char, out: *mut CsrCaesar) -> c_int {
+    if spec.is_null() || out.is_null() {
+        return -1;
+    }
+
+    match CStr::from_ptr(spec).to_str().map(str::parse::<Caesar>) {
+        Ok(Ok(caesar)) => {
+            *out = caesar.into();
+            0
+        }
+        _ => -1,
+    }
+}
+
+/// Returns the cipher that undoes `caesar`.
+#[no_mangle]
+pub extern "C" fn csr_caesar_inverse(caesar: CsrCaesar) -> CsrCaesar {
+    Caesar::from(caesar).inverse().into()
+}
+
+/// Encrypts `len` bytes at `buf` in place. Only ASCII letters change, so
+/// UTF-8 (or any ASCII-compatible encoding) stays valid. Does nothing if
+/// `buf` is null.
+///
+/// # Safety
+///
+/// `buf` must be null or valid for reads and writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn csr_encrypt_buf(caesar: CsrCaesar, buf: *mut u8, len: usize) {
+    if !buf.is_null() {
+        Caesar::from(caesar).encrypt_bytes(slice::from_raw_parts_mut(buf, len));
+    }
+}
+
+/// Decrypts `len` bytes at `buf` in place. Does nothing if `buf` is null.
+///
+/// # Safety
+///
+/// `buf` must be null or valid for reads and writes of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn csr_decrypt_buf(caesar: CsrCaesar, buf: *mut u8, len: usize) {
+    if !buf.is_null() {
+        Caesar::from(caesar).decrypt_bytes(slice::from_raw_parts_mut(buf, len));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn buffers_roundtrip() {
+        let caesar = csr_caesar_new(-24);
+        let mut buf = *b"Attack at dawn!";
+
+        assert_eq!(caesar.shift, 2);
+
+        unsafe {
+            csr_encrypt_buf(caesar, buf.as_mut_ptr(), buf.len());
+            assert_eq!(&buf, b"Cvvcem cv fcyp!");
+
+            csr_encrypt_buf(csr_caesar_inverse(caesar), buf.as_mut_ptr(), buf.len());
+            assert_eq!(&buf, b"Attack at dawn!");
+
+            csr_decrypt_buf(caesar, ptr::null_mut(), 10);
+        }
+    }
+
+    #[test]
+    fn parse_specs() {
+        let mut out = CsrCaesar { shift: 99 };
+
+        unsafe {
+            assert_eq!(csr_caesar_parse(b"rot13\0".as_ptr().cast(), &mut out), 0);
+            assert_eq!(out.shift, 13);
+
+            assert_eq!(csr_caesar_parse(b"nope\0".as_ptr().cast(), &mut out), -1);
+            assert_eq!(csr_caesar_parse(ptr::null(), &mut out), -1);
+            assert_eq!(out.shift, 13);
+        }
+    }
+}
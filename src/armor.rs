@@ -0,0 +1,256 @@
+use crate::{codec, Caesar, Error};
+use std::fmt;
+use std::str::FromStr;
+
+const BEGIN: &str = "-----BEGIN CSR MESSAGE-----";
+const END: &str = "-----END CSR MESSAGE-----";
+const LINE_WIDTH: usize = 64;
+
+/// An ASCII-armored message, modelled on PEM: a begin line, `Name: value`
+/// headers, a blank line, the ciphertext as wrapped Base64, and an end
+/// line. Armor survives being pasted through email or chat, and parsing
+/// skips any text around it.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Armor, Caesar};
+///
+/// let caesar = Caesar::new(3);
+/// let armor = Armor::encrypt(&caesar, "Hello!")
+///     .header("Key-Hint", "small primes")
+///     .unwrap();
+///
+/// let text = armor.to_string();
+/// assert_eq!(
+///     text,
+///     "-----BEGIN CSR MESSAGE-----\n\
+///      Cipher: caesar\n\
+///      Key-Hint: small primes\n\
+///      \n\
+///      S2hvb3Ih\n\
+///      -----END CSR MESSAGE-----\n"
+/// );
+///
+/// let parsed: Armor = text.parse().unwrap();
+/// assert_eq!(parsed.get("key-hint"), Some("small primes"));
+/// assert_eq!(parsed.decrypt(&caesar).unwrap(), "Hello!");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Armor {
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Armor {
+    /// Wraps already encrypted bytes, with no headers.
+    pub fn new<B: Into<Vec<u8>>>(body: B) -> Self {
+        Armor {
+            headers: Vec::new(),
+            body: body.into(),
+        }
+    }
+
+    /// Encrypts `plaintext` and wraps the ciphertext, with a `Cipher:
+    /// caesar` header. The key itself is never written.
+    pub fn encrypt<S: AsRef<str>>(caesar: &Caesar, plaintext: S) -> Self {
+        let mut armor = Armor::new(caesar.encrypt(plaintext));
+        armor.headers.push(("Cipher".into(), "caesar".into()));
+        armor
+    }
+
+    /// Decrypts the body. Fails with [`Error::InvalidArmor`] if there is a
+    /// `Cipher` header naming something other than caesar, or with
+    /// [`Error::InvalidEncoding`] if the plaintext isn't UTF-8.
+    pub fn decrypt(&self, caesar: &Caesar) -> Result<String, Error> {
+        if self
+            .get("Cipher")
+            .is_some_and(|cipher| !cipher.eq_ignore_ascii_case("caesar"))
+        {
+            return Err(Error::InvalidArmor);
+        }
+
+        let mut bytes = self.body.clone();
+        caesar.decrypt_bytes(&mut bytes);
+        String::from_utf8(bytes).map_err(|_| Error::InvalidEncoding)
+    }
+
+    /// Adds a header. Names can't contain `:`, neither can hold line
+    /// breaks, and neither can start or end with whitespace, which parsing
+    /// ignores (and pasting often mangles). That way headers are written on
+    /// one line and parse back the same. Anything else fails with
+    /// [`Error::InvalidArmor`].
+    pub fn header<N: Into<String>, V: Into<String>>(
+        mut self,
+        name: N,
+        value: V,
+    ) -> Result<Self, Error> {
+        let (name, value) = (name.into(), value.into());
+        let fits = |s: &str| !s.contains(['\n', '\r']) && s.trim() == s;
+
+        if name.contains(':') || !fits(&name) || !fits(&value) {
+            return Err(Error::InvalidArmor);
+        }
+
+        self.headers.push((name, value));
+        Ok(self)
+    }
+
+    /// Returns the value of the first header with this name, ignoring
+    /// ASCII case.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns every header in order.
+    pub fn headers(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    /// Returns the armored bytes.
+    pub fn body(&self) -> &[u8] {
+        &self.body
+    }
+
+    /// Unwraps the armored bytes.
+    pub fn into_body(self) -> Vec<u8> {
+        self.body
+    }
+}
+
+impl fmt::Display for Armor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", BEGIN)?;
+
+        for (name, value) in &self.headers {
+            writeln!(f, "{}: {}", name, value)?;
+        }
+
+        if !self.headers.is_empty() {
+            writeln!(f)?;
+        }
+
+        // Base64 is plain ASCII, so splitting on bytes is safe
+        let body = codec::encode_base64(&self.body);
+        for line in body.as_bytes().chunks(LINE_WIDTH) {
+            writeln!(f, "{}", String::from_utf8_lossy(line))?;
+        }
+
+        writeln!(f, "{}", END)
+    }
+}
+
+impl FromStr for Armor {
+    type Err = Error;
+
+    /// Parses the first armored message in `s`, ignoring any text before
+    /// or after it and any trailing whitespace on its lines.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .map(str::trim_end)
+            .skip_while(|line| line.trim_start() != BEGIN)
+            .skip(1);
+
+        let mut armor = Armor::new(Vec::new());
+        let mut body = String::new();
+        let mut in_headers = true;
+
+        loop {
+            let line = lines.next().ok_or(Error::InvalidArmor)?;
+
+            if line == END {
+                break;
+            }
+
+            if in_headers {
+                if let Some((name, value)) = line.split_once(':') {
+                    armor
+                        .headers
+                        .push((name.trim().into(), value.trim().into()));
+                    continue;
+                }
+
+                in_headers = false;
+                if line.is_empty() {
+                    continue;
+                }
+            }
+
+            body.push_str(line.trim_start());
+        }
+
+        armor.body = codec::decode_base64(body)?;
+        Ok(armor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let caesar = Caesar::new(11);
+        let plaintext = "The quick brown fox jumps over the lazy dog. ".repeat(4);
+        let armor = Armor::encrypt(&caesar, &plaintext);
+        let text = armor.to_string();
+
+        assert!(text.lines().all(|line| line.len() <= LINE_WIDTH));
+
+        let pasted = format!(
+            "> see below\r\n\r\n{}\r\nthanks!\r\n",
+            text.replace('\n', "\r\n")
+        );
+        let parsed: Armor = pasted.parse().unwrap();
+
+        assert_eq!(parsed, armor);
+        assert_eq!(parsed.decrypt(&caesar).unwrap(), plaintext);
+    }
+
+    #[test]
+    fn without_headers() {
+        let armor = Armor::new(b"Khoor".to_vec());
+        let text = armor.to_string();
+
+        assert_eq!(text, format!("{}\nS2hvb3I=\n{}\n", BEGIN, END));
+        assert_eq!(text.parse::<Armor>().unwrap(), armor);
+        assert_eq!(armor.decrypt(&Caesar::new(3)).unwrap(), "Hello");
+    }
+
+    #[test]
+    fn malformed() {
+        assert_eq!("no armor here".parse::<Armor>(), Err(Error::InvalidArmor));
+        assert_eq!(
+            format!("{}\nS2hv", BEGIN).parse::<Armor>(),
+            Err(Error::InvalidArmor)
+        );
+        assert_eq!(
+            format!("{}\n\nnot base64!\n{}", BEGIN, END).parse::<Armor>(),
+            Err(Error::InvalidEncoding)
+        );
+
+        let other = Armor::new(Vec::new()).header("Cipher", "vigenere").unwrap();
+        assert_eq!(other.decrypt(&Caesar::new(1)), Err(Error::InvalidArmor));
+    }
+
+    #[test]
+    fn invalid_headers() {
+        for (name, value) in [
+            ("Key-Hint", "one\ntwo"),
+            ("Key-Hint", "hint "),
+            (" Key-Hint", "hint"),
+            ("Key:Hint", "hint"),
+        ]
+        .iter()
+        {
+            assert_eq!(
+                Armor::new(Vec::new()).header(*name, *value),
+                Err(Error::InvalidArmor)
+            );
+        }
+    }
+}
@@ -0,0 +1,103 @@
+use crate::{Caesar, Error};
+use num::cast::AsPrimitive;
+use num::Zero;
+
+/// A builder for configuring a [`Caesar`](crate::Caesar). Created with
+/// [`Caesar::builder`](crate::Caesar::builder).
+///
+/// # Example
+///
+/// ```
+/// use csr::Caesar;
+///
+/// let c = Caesar::builder().shift(5).strict(true).build().unwrap();
+/// assert_eq!(c.encrypt("abc"), "fgh");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CaesarBuilder {
+    // the reduced shift, and whether it was in range before reducing
+    shift: Option<(u8, bool)>,
+    strict: bool,
+}
+
+impl CaesarBuilder {
+    /// Constructs a new builder with no shift set and strict mode off.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the shift to use. This is required.
+    pub fn shift<U>(mut self, shift: U) -> Self
+    where
+        U: AsPrimitive<i128> + AsPrimitive<u128> + PartialOrd + Zero,
+    {
+        self.shift = Some(crate::reduce(shift));
+        self
+    }
+
+    /// Enables or disables strict mode. In strict mode, shifts outside of
+    /// 0 to 25 are rejected instead of being reduced.
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Builds the Caesar, failing if no shift was provided or if the shift
+    /// is out of range in strict mode.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::{Caesar, Error};
+    ///
+    /// assert_eq!(Caesar::builder().build().err(), Some(Error::MissingShift));
+    /// assert_eq!(
+    ///     Caesar::builder().shift(30).strict(true).build().err(),
+    ///     Some(Error::ShiftOutOfRange)
+    /// );
+    /// ```
+    pub fn build(self) -> Result<Caesar, Error> {
+        let (shift, in_range) = self.shift.ok_or(Error::MissingShift)?;
+
+        if self.strict && !in_range {
+            return Err(Error::ShiftOutOfRange);
+        }
+
+        Ok(Caesar::new(shift))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lenient_reduces() {
+        let caesar = Caesar::builder().shift(27).build().unwrap();
+
+        assert_eq!(caesar, Caesar::new(1));
+    }
+
+    #[test]
+    fn strict_accepts_in_range() {
+        let caesar = Caesar::builder().shift(25).strict(true).build().unwrap();
+
+        assert_eq!(caesar.encrypt("b"), "a");
+    }
+
+    #[test]
+    fn strict_rejects_negative() {
+        let builder = Caesar::builder().shift(-1);
+
+        assert_eq!(builder.build(), Ok(Caesar::new(25)));
+        assert_eq!(builder.strict(true).build(), Err(Error::ShiftOutOfRange));
+    }
+
+    #[test]
+    fn huge_unsigned() {
+        let builder = Caesar::builder().shift(u128::MAX);
+
+        assert_eq!(builder.build(), Ok(Caesar::new(21)));
+        assert_eq!(builder.strict(true).build(), Err(Error::ShiftOutOfRange));
+    }
+}
@@ -0,0 +1,22 @@
+use crate::Caesar;
+
+/// A cipher that can encrypt and decrypt strings. This is what lets
+/// different ciphers be composed, for example with a
+/// [`Pipeline`](crate::Pipeline).
+pub trait Cipher {
+    /// Encrypts a string, returning the ciphertext.
+    fn encrypt(&self, buf: &str) -> String;
+
+    /// Decrypts a string, returning the plaintext.
+    fn decrypt(&self, buf: &str) -> String;
+}
+
+impl Cipher for Caesar {
+    fn encrypt(&self, buf: &str) -> String {
+        Caesar::encrypt(self, buf)
+    }
+
+    fn decrypt(&self, buf: &str) -> String {
+        Caesar::decrypt(self, buf)
+    }
+}
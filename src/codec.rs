@@ -0,0 +1,224 @@
+//! Hex and Base64 encodings for ciphertext.
+//!
+//! A Caesar shift keeps text as text, but ciphertext often ends up next to
+//! byte-level transforms or in places that only accept a narrow alphabet.
+//! These functions turn arbitrary bytes into hex or standard, padded Base64
+//! and back, and [`Caesar`] has helpers that encrypt then encode, or decode
+//! then decrypt, in one step.
+//!
+//! # Example
+//!
+//! ```
+//! use csr::{codec, Caesar};
+//!
+//! let caesar = Caesar::new(3);
+//! let encoded = caesar.encrypt_base64("Hello!");
+//! assert_eq!(encoded, "S2hvb3Ih");
+//! assert_eq!(codec::decode_base64(&encoded).unwrap(), b"Khoor!");
+//! assert_eq!(caesar.decrypt_base64(&encoded).unwrap(), "Hello!");
+//! ```
+
+use crate::{Caesar, Error};
+
+const HEX: &[u8; 16] = b"0123456789abcdef";
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes bytes as lowercase hex.
+pub fn encode_hex<B: AsRef<[u8]>>(bytes: B) -> String {
+    let bytes = bytes.as_ref();
+    let mut out = String::with_capacity(bytes.len() * 2);
+
+    for &b in bytes {
+        out.push(HEX[(b >> 4) as usize] as char);
+        out.push(HEX[(b & 0xf) as usize] as char);
+    }
+
+    out
+}
+
+/// Decodes hex in either case. Fails with [`Error::InvalidEncoding`] on an
+/// odd number of digits or anything that isn't a hex digit.
+pub fn decode_hex<S: AsRef<str>>(hex: S) -> Result<Vec<u8>, Error> {
+    let hex = hex.as_ref().as_bytes();
+
+    if hex.len() % 2 != 0 {
+        return Err(Error::InvalidEncoding);
+    }
+
+    hex.chunks_exact(2)
+        .map(|pair| Ok(hex_digit(pair[0])? << 4 | hex_digit(pair[1])?))
+        .collect()
+}
+
+/// Encodes bytes as standard Base64 with padding.
+pub fn encode_base64<B: AsRef<[u8]>>(bytes: B) -> String {
+    let bytes = bytes.as_ref();
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (b as u32) << (16 - 8 * i));
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+
+    out
+}
+
+/// Decodes standard Base64. Padding is optional, but anything outside the
+/// alphabet, including whitespace, fails with [`Error::InvalidEncoding`].
+pub fn decode_base64<S: AsRef<str>>(base64: S) -> Result<Vec<u8>, Error> {
+    let mut input = base64.as_ref().as_bytes();
+
+    if input.len() % 4 == 0 {
+        // at most two padding characters, and only at the end
+        for _ in 0..2 {
+            if let [rest @ .., b'='] = input {
+                input = rest;
+            }
+        }
+    }
+
+    if input.len() % 4 == 1 {
+        return Err(Error::InvalidEncoding);
+    }
+
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+
+    for chunk in input.chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            n |= base64_digit(c)? << (18 - 6 * i);
+        }
+
+        for i in 0..chunk.len() - 1 {
+            out.push((n >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    Ok(out)
+}
+
+fn hex_digit(c: u8) -> Result<u8, Error> {
+    match c {
+        b'0'..=b'9' => Ok(c - b'0'),
+        b'a'..=b'f' => Ok(c - b'a' + 10),
+        b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => Err(Error::InvalidEncoding),
+    }
+}
+
+fn base64_digit(c: u8) -> Result<u32, Error> {
+    match c {
+        b'A'..=b'Z' => Ok((c - b'A') as u32),
+        b'a'..=b'z' => Ok((c - b'a' + 26) as u32),
+        b'0'..=b'9' => Ok((c - b'0' + 52) as u32),
+        b'+' => Ok(62),
+        b'/' => Ok(63),
+        _ => Err(Error::InvalidEncoding),
+    }
+}
+
+impl Caesar {
+    /// Encrypts a string and encodes the ciphertext as hex.
+    pub fn encrypt_hex<S: AsRef<str>>(&self, plaintext: S) -> String {
+        encode_hex(self.encrypt(plaintext))
+    }
+
+    /// Decodes hex ciphertext and decrypts it. Fails with
+    /// [`Error::InvalidEncoding`] if the input isn't hex or doesn't decode
+    /// to UTF-8.
+    pub fn decrypt_hex<S: AsRef<str>>(&self, hex: S) -> Result<String, Error> {
+        self.decrypt_decoded(decode_hex(hex)?)
+    }
+
+    /// Encrypts a string and encodes the ciphertext as Base64.
+    pub fn encrypt_base64<S: AsRef<str>>(&self, plaintext: S) -> String {
+        encode_base64(self.encrypt(plaintext))
+    }
+
+    /// Decodes Base64 ciphertext and decrypts it. Fails with
+    /// [`Error::InvalidEncoding`] if the input isn't Base64 or doesn't
+    /// decode to UTF-8.
+    pub fn decrypt_base64<S: AsRef<str>>(&self, base64: S) -> Result<String, Error> {
+        self.decrypt_decoded(decode_base64(base64)?)
+    }
+
+    fn decrypt_decoded(&self, mut bytes: Vec<u8>) -> Result<String, Error> {
+        self.decrypt_bytes(&mut bytes);
+        String::from_utf8(bytes).map_err(|_| Error::InvalidEncoding)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        let hex = encode_hex(&bytes);
+
+        assert_eq!(&hex[..8], "00010203");
+        assert_eq!(decode_hex(&hex).unwrap(), bytes);
+        assert_eq!(decode_hex("DEADbeef").unwrap(), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(decode_hex("abc"), Err(Error::InvalidEncoding));
+        assert_eq!(decode_hex("zz"), Err(Error::InvalidEncoding));
+    }
+
+    #[test]
+    fn base64_vectors() {
+        let vectors = [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ];
+
+        for (plain, encoded) in vectors.iter() {
+            assert_eq!(encode_base64(plain), *encoded);
+            assert_eq!(decode_base64(encoded).unwrap(), plain.as_bytes());
+        }
+
+        assert_eq!(decode_base64("Zm9vYg").unwrap(), b"foob");
+        assert_eq!(decode_base64("Zm9vY"), Err(Error::InvalidEncoding));
+        assert_eq!(decode_base64("Zm9v\n"), Err(Error::InvalidEncoding));
+        assert_eq!(decode_base64("Zg=a"), Err(Error::InvalidEncoding));
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        let bytes: Vec<u8> = (0..=255).rev().collect();
+
+        for len in 0..bytes.len() {
+            let encoded = encode_base64(&bytes[..len]);
+            assert_eq!(decode_base64(&encoded).unwrap(), &bytes[..len]);
+        }
+    }
+
+    #[test]
+    fn caesar_helpers() {
+        let caesar = Caesar::new(13);
+
+        assert_eq!(caesar.encrypt_hex("Hi"), "5576");
+        assert_eq!(caesar.decrypt_hex("5576").unwrap(), "Hi");
+        assert_eq!(caesar.decrypt_hex("ff"), Err(Error::InvalidEncoding));
+        assert_eq!(
+            caesar
+                .decrypt_base64(caesar.encrypt_base64("Hello, world!"))
+                .unwrap(),
+            "Hello, world!"
+        );
+    }
+}
@@ -0,0 +1,110 @@
+use crate::Caesar;
+
+// relative frequencies of a to z in English text, in percent
+const ENGLISH: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// A possible decryption found by [`crack`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    /// The Caesar that decrypts the ciphertext into `plaintext`.
+    pub caesar: Caesar,
+    /// How far the plaintext's letter frequencies are from English, as a
+    /// chi-squared statistic. Lower is more English-like.
+    pub score: f64,
+    /// The ciphertext decrypted with `caesar`.
+    pub plaintext: String,
+}
+
+/// Brute-forces a ciphertext by trying all 26 shifts, and ranks the results
+/// by how closely their letter frequencies match English. The best
+/// candidate comes first. Ties (such as input without letters) keep shift
+/// order.
+///
+/// # Example
+///
+/// ```
+/// use csr::{crack, Caesar};
+///
+/// let ciphertext = Caesar::new(7).encrypt("The quick brown fox jumps over the lazy dog");
+/// let best = &crack(&ciphertext)[0];
+/// assert_eq!(best.caesar, Caesar::new(7));
+/// assert_eq!(best.plaintext, "The quick brown fox jumps over the lazy dog");
+/// ```
+pub fn crack<S: AsRef<str>>(ciphertext: S) -> Vec<Candidate> {
+    let ciphertext = ciphertext.as_ref();
+    let counts = letter_counts(ciphertext);
+
+    let mut candidates: Vec<Candidate> = (0..26u8)
+        .map(|shift| {
+            let caesar = Caesar::new(shift);
+
+            Candidate {
+                caesar,
+                score: chi_squared(&counts, shift),
+                plaintext: caesar.decrypt(ciphertext),
+            }
+        })
+        .collect();
+
+    candidates.sort_by(|a, b| a.score.total_cmp(&b.score));
+    candidates
+}
+
+pub(crate) fn letter_counts(text: &str) -> [u64; 26] {
+    let mut counts = [0; 26];
+
+    for c in text.bytes().filter(u8::is_ascii_alphabetic) {
+        counts[(c.to_ascii_lowercase() - b'a') as usize] += 1;
+    }
+
+    counts
+}
+
+// scores the ciphertext counts as if decrypted with shift, without
+// actually decrypting: plaintext letter i was ciphertext letter i + shift
+fn chi_squared(counts: &[u64; 26], shift: u8) -> f64 {
+    let total: u64 = counts.iter().sum();
+
+    if total == 0 {
+        return 0.0;
+    }
+
+    ENGLISH
+        .iter()
+        .enumerate()
+        .map(|(plain, frequency)| {
+            let observed = counts[(plain + shift as usize) % 26] as f64;
+            let expected = frequency / 100.0 * total as f64;
+            (observed - expected).powi(2) / expected
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_every_shift() {
+        let plaintext = "Frequency analysis works well on ordinary English sentences.";
+
+        for shift in 0..26u8 {
+            let candidates = crack(Caesar::new(shift).encrypt(plaintext));
+
+            assert_eq!(candidates.len(), 26);
+            assert_eq!(candidates[0].caesar, Caesar::new(shift));
+            assert_eq!(candidates[0].plaintext, plaintext);
+        }
+    }
+
+    #[test]
+    fn no_letters_keeps_shift_order() {
+        let candidates = crack("1234 !?");
+
+        assert!(candidates.iter().all(|c| c.score == 0.0));
+        assert_eq!(candidates[5].caesar, Caesar::new(5));
+    }
+}
@@ -0,0 +1,126 @@
+use crate::Caesar;
+use std::fmt::{self, Write};
+
+/// A lazy [`Display`](std::fmt::Display) adapter that encrypts a string
+/// while it is being formatted, without allocating an intermediate String.
+/// Created with [`Caesar::display`](crate::Caesar::display).
+///
+/// Width, fill, alignment and precision are honoured like they are for
+/// `str`, but using them does allocate, since padding needs the length of
+/// the output first.
+///
+/// # Example
+///
+/// ```
+/// use csr::Caesar;
+///
+/// let c = Caesar::new(2);
+/// let line = format!("user said: {}", c.display("Attack at dawn!"));
+/// assert_eq!(line, "user said: Cvvcem cv fcyp!");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Encrypted<'a> {
+    caesar: Caesar,
+    buf: &'a str,
+}
+
+impl<'a> Encrypted<'a> {
+    pub(crate) fn new(caesar: Caesar, buf: &'a str) -> Self {
+        Encrypted { caesar, buf }
+    }
+}
+
+impl fmt::Display for Encrypted<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if f.width().is_some() || f.precision().is_some() {
+            return f.pad(&self.caesar.encrypt(self.buf));
+        }
+
+        for c in self.buf.chars() {
+            f.write_char(self.caesar.encrypt_char(c))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A [`fmt::Write`](std::fmt::Write) sink that encrypts everything written
+/// to it before passing it on, so `write!` produces ciphertext directly
+/// without allocating.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Caesar, EncryptingWriter};
+/// use std::fmt::Write;
+///
+/// let mut sink = EncryptingWriter::new(Caesar::new(2), String::new());
+/// write!(sink, "{} at {}!", "Attack", "dawn").unwrap();
+/// assert_eq!(sink.into_inner(), "Cvvcem cv fcyp!");
+/// ```
+#[derive(Debug, Clone)]
+pub struct EncryptingWriter<W> {
+    caesar: Caesar,
+    inner: W,
+}
+
+impl<W: Write> EncryptingWriter<W> {
+    /// Constructs a sink that encrypts with `caesar` and writes to `inner`.
+    pub fn new(caesar: Caesar, inner: W) -> Self {
+        EncryptingWriter { caesar, inner }
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Consumes the sink, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for EncryptingWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        write!(self.inner, "{}", self.caesar.display(s))
+    }
+
+    fn write_char(&mut self, c: char) -> fmt::Result {
+        self.inner.write_char(self.caesar.encrypt_char(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_encrypt() {
+        let caesar = Caesar::new(9);
+        let input = "Logging 🙂 pipelines, obfuscated!";
+
+        assert_eq!(caesar.display(input).to_string(), caesar.encrypt(input));
+    }
+
+    #[test]
+    fn formatting_flags() {
+        let c = Caesar::new(1);
+
+        assert_eq!(format!("[{:>8}]", c.display("abc")), "[     bcd]");
+        assert_eq!(format!("[{:-<6.2}]", c.display("abc")), "[bc----]");
+        assert_eq!(format!("[{:^5}]", c.display("abc")), "[ bcd ]");
+    }
+
+    #[test]
+    fn writer_matches_encrypt() {
+        let caesar = Caesar::new(21);
+        let mut sink = EncryptingWriter::new(caesar, String::new());
+
+        let (word, c, n) = ("mixed", 'c', 42);
+        write!(sink, "{}-{:?}-{}", word, c, n).unwrap();
+        sink.write_char('Z').unwrap();
+
+        assert_eq!(sink.get_ref(), &caesar.encrypt("mixed-'c'-42Z"));
+    }
+}
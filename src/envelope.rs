@@ -0,0 +1,233 @@
+use crate::{Armor, Caesar, Error};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// A sealed message with the metadata needed to open it: which cipher was
+/// used, optionally the key, when it was sealed, and the ciphertext.
+///
+/// The wire format is an [`Armor`] block with `Cipher`, `Timestamp` (Unix
+/// seconds), and, if the key was included, `Shift` headers, so envelopes
+/// can be exchanged as text and read without this crate.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Caesar, Envelope};
+///
+/// let caesar = Caesar::new(7);
+///
+/// // without the key, the receiver has to supply it
+/// let sealed = Envelope::seal(&caesar, "meet at noon", false).to_string();
+/// let envelope: Envelope = sealed.parse().unwrap();
+/// assert!(envelope.open().is_err());
+/// assert_eq!(envelope.open_with(&caesar).unwrap(), "meet at noon");
+///
+/// // with it, the envelope opens itself
+/// let envelope = Envelope::seal(&caesar, "meet at noon", true);
+/// assert_eq!(envelope.shift(), Some(7));
+/// assert_eq!(envelope.open().unwrap(), "meet at noon");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Envelope {
+    cipher: String,
+    shift: Option<u8>,
+    timestamp: SystemTime,
+    ciphertext: Vec<u8>,
+}
+
+impl Envelope {
+    /// Encrypts `plaintext` into an envelope stamped with the current time,
+    /// to the second. The key is only recorded if `include_key` is true.
+    pub fn seal<S: AsRef<str>>(caesar: &Caesar, plaintext: S, include_key: bool) -> Self {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        Envelope {
+            cipher: "caesar".into(),
+            shift: if include_key {
+                Some(caesar.shift())
+            } else {
+                None
+            },
+            timestamp: UNIX_EPOCH + Duration::from_secs(secs),
+            ciphertext: caesar.encrypt(plaintext).into_bytes(),
+        }
+    }
+
+    /// Opens the envelope with the key it carries. Fails with
+    /// [`Error::MissingShift`] if it was sealed without one.
+    pub fn open(&self) -> Result<String, Error> {
+        let shift = self.shift.ok_or(Error::MissingShift)?;
+        self.open_with(&Caesar::new(shift))
+    }
+
+    /// Opens the envelope with the given key, ignoring any it carries.
+    /// Fails with [`Error::InvalidEncoding`] if the plaintext isn't UTF-8.
+    pub fn open_with(&self, caesar: &Caesar) -> Result<String, Error> {
+        let mut bytes = self.ciphertext.clone();
+        caesar.decrypt_bytes(&mut bytes);
+        String::from_utf8(bytes).map_err(|_| Error::InvalidEncoding)
+    }
+
+    /// Returns the cipher identifier, currently always `caesar`.
+    pub fn cipher(&self) -> &str {
+        &self.cipher
+    }
+
+    /// Returns the shift, if it was included when sealing.
+    pub fn shift(&self) -> Option<u8> {
+        self.shift
+    }
+
+    /// Returns when the envelope was sealed.
+    pub fn timestamp(&self) -> SystemTime {
+        self.timestamp
+    }
+
+    /// Returns the ciphertext.
+    pub fn ciphertext(&self) -> &[u8] {
+        &self.ciphertext
+    }
+}
+
+impl From<Envelope> for Armor {
+    fn from(envelope: Envelope) -> Self {
+        let secs = envelope
+            .timestamp
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+
+        // the cipher is always `caesar` and the rest are numbers, so every
+        // header fits on one line
+        let shift = envelope.shift;
+        let armor = Armor::new(envelope.ciphertext)
+            .header("Cipher", envelope.cipher)
+            .and_then(|armor| armor.header("Timestamp", secs.to_string()))
+            .and_then(|armor| match shift {
+                Some(shift) => armor.header("Shift", shift.to_string()),
+                None => Ok(armor),
+            });
+
+        armor.expect("envelope metadata is always a valid header")
+    }
+}
+
+impl TryFrom<Armor> for Envelope {
+    type Error = Error;
+
+    /// Reads an envelope back from armor. Fails with
+    /// [`Error::InvalidArmor`] if the cipher isn't caesar or the
+    /// `Timestamp` header is missing or malformed.
+    fn try_from(armor: Armor) -> Result<Self, Self::Error> {
+        let cipher = armor.get("Cipher").ok_or(Error::InvalidArmor)?;
+        if !cipher.eq_ignore_ascii_case("caesar") {
+            return Err(Error::InvalidArmor);
+        }
+
+        let secs = armor
+            .get("Timestamp")
+            .and_then(|t| t.parse().ok())
+            .ok_or(Error::InvalidArmor)?;
+
+        // untrusted input can name a time SystemTime can't represent
+        let timestamp = UNIX_EPOCH
+            .checked_add(Duration::from_secs(secs))
+            .ok_or(Error::InvalidArmor)?;
+
+        let shift = match armor.get("Shift") {
+            Some(shift) => Some(shift.parse::<Caesar>()?.shift()),
+            None => None,
+        };
+
+        Ok(Envelope {
+            cipher: cipher.to_ascii_lowercase(),
+            shift,
+            timestamp,
+            ciphertext: armor.into_body(),
+        })
+    }
+}
+
+impl fmt::Display for Envelope {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Armor::from(self.clone()).fmt(f)
+    }
+}
+
+impl FromStr for Envelope {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Envelope::try_from(s.parse::<Armor>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wire_round_trip() {
+        let caesar = Caesar::new(20);
+
+        for &include_key in &[false, true] {
+            let envelope = Envelope::seal(&caesar, "Attack at dawn!", include_key);
+            let parsed: Envelope = envelope.to_string().parse().unwrap();
+
+            assert_eq!(parsed, envelope);
+            assert_eq!(parsed.open_with(&caesar).unwrap(), "Attack at dawn!");
+        }
+    }
+
+    #[test]
+    fn wire_format() {
+        let envelope = Envelope {
+            cipher: "caesar".into(),
+            shift: Some(3),
+            timestamp: UNIX_EPOCH + Duration::from_secs(1_600_000_000),
+            ciphertext: b"Khoor".to_vec(),
+        };
+
+        assert_eq!(
+            envelope.to_string(),
+            "-----BEGIN CSR MESSAGE-----\n\
+             Cipher: caesar\n\
+             Timestamp: 1600000000\n\
+             Shift: 3\n\
+             \n\
+             S2hvb3I=\n\
+             -----END CSR MESSAGE-----\n"
+        );
+        assert_eq!(envelope.open().unwrap(), "Hello");
+    }
+
+    #[test]
+    fn rejects_bad_metadata() {
+        let armor = |headers: &[(&str, &str)]| {
+            headers
+                .iter()
+                .fold(Armor::new(Vec::new()), |armor, (name, value)| {
+                    armor.header(*name, *value).unwrap()
+                })
+        };
+
+        let no_time = armor(&[("Cipher", "caesar")]);
+        assert_eq!(Envelope::try_from(no_time), Err(Error::InvalidArmor));
+
+        let bad_shift = armor(&[("Cipher", "caesar"), ("Timestamp", "0"), ("Shift", "loads")]);
+        assert_eq!(Envelope::try_from(bad_shift), Err(Error::InvalidSpec));
+
+        let other = armor(&[("Cipher", "rot47"), ("Timestamp", "0")]);
+        assert_eq!(Envelope::try_from(other), Err(Error::InvalidArmor));
+
+        let max = u64::MAX.to_string();
+        let armor = armor(&[("Cipher", "caesar"), ("Timestamp", &max)]);
+        assert_eq!(
+            armor.to_string().parse::<Envelope>(),
+            Err(Error::InvalidArmor)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::error;
+use std::fmt;
+
+/// The error type for fallible operations in this crate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// No shift was given: a builder was built without one, or an envelope
+    /// sealed without its key was opened.
+    MissingShift,
+    /// The shift was out of range and strict mode was enabled.
+    ShiftOutOfRange,
+    /// A cipher spec could not be parsed.
+    InvalidSpec,
+    /// Encoded input was malformed or didn't decode to valid text.
+    InvalidEncoding,
+    /// An armored message was missing its delimiters, used another cipher,
+    /// or had a header that can't be written on one line.
+    InvalidArmor,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingShift => write!(f, "no shift was provided or recorded"),
+            Error::ShiftOutOfRange => write!(f, "shift must be between 0 and 25"),
+            Error::InvalidSpec => write!(f, "invalid cipher spec"),
+            Error::InvalidEncoding => write!(f, "invalid encoded input"),
+            Error::InvalidArmor => write!(f, "invalid armored message"),
+        }
+    }
+}
+
+impl error::Error for Error {}
@@ -0,0 +1,159 @@
+use crate::Caesar;
+use std::fmt;
+
+/// What happened to one character during encryption or decryption.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// The character before the step.
+    pub original: char,
+    /// Its position in the alphabet, counting `a` and `A` as 0, or `None`
+    /// if it isn't an ASCII letter and passed through unchanged.
+    pub position: Option<u8>,
+    /// The shift applied: positive when encrypting, negative when
+    /// decrypting, and 0 for characters that aren't letters.
+    pub shift: i8,
+    /// The character after the step.
+    pub result: char,
+}
+
+impl fmt::Display for Step {
+    /// Formats the step as one line, such as `'h'  7  +3 -> 'k' 10`.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.position {
+            Some(position) => {
+                let after = (position as i8 + self.shift).rem_euclid(26);
+                write!(
+                    f,
+                    "{:?} {:>2} {:+3} -> {:?} {:>2}",
+                    self.original, position, self.shift, self.result, after
+                )
+            }
+            None => write!(f, "{:?} unchanged", self.original),
+        }
+    }
+}
+
+/// A character-by-character trace of a transformation, from
+/// [`Caesar::explain_encrypt`] or [`Caesar::explain_decrypt`]. Displays as
+/// one step per line.
+///
+/// # Example
+///
+/// ```
+/// use csr::Caesar;
+///
+/// let explanation = Caesar::new(3).explain_encrypt("Hi!");
+///
+/// assert_eq!(explanation.output(), "Kl!");
+/// assert_eq!(
+///     explanation.to_string(),
+///     "'H'  7  +3 -> 'K' 10\n\
+///      'i'  8  +3 -> 'l' 11\n\
+///      '!' unchanged\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Explanation {
+    steps: Vec<Step>,
+}
+
+impl Explanation {
+    /// Returns the steps, one per character of the input.
+    pub fn steps(&self) -> &[Step] {
+        &self.steps
+    }
+
+    /// Returns the transformed text.
+    pub fn output(&self) -> String {
+        self.steps.iter().map(|step| step.result).collect()
+    }
+}
+
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for step in &self.steps {
+            writeln!(f, "{}", step)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Caesar {
+    /// Encrypts a string, recording what happened to each character.
+    pub fn explain_encrypt<S: AsRef<str>>(&self, plaintext: S) -> Explanation {
+        self.explain(plaintext.as_ref(), self.shift as i8, |c| {
+            self.encrypt_char(c)
+        })
+    }
+
+    /// Decrypts a string, recording what happened to each character.
+    pub fn explain_decrypt<S: AsRef<str>>(&self, ciphertext: S) -> Explanation {
+        self.explain(ciphertext.as_ref(), -(self.shift as i8), |c| {
+            self.decrypt_char(c)
+        })
+    }
+
+    fn explain<F: Fn(char) -> char>(&self, input: &str, shift: i8, f: F) -> Explanation {
+        let steps = input
+            .chars()
+            .map(|original| {
+                let position = match original {
+                    'a'..='z' => Some(original as u8 - b'a'),
+                    'A'..='Z' => Some(original as u8 - b'A'),
+                    _ => None,
+                };
+
+                Step {
+                    original,
+                    position,
+                    shift: if position.is_some() { shift } else { 0 },
+                    result: f(original),
+                }
+            })
+            .collect();
+
+        Explanation { steps }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_cipher() {
+        let caesar = Caesar::new(19);
+        let text = "Explain this, step by step: ÀZ";
+
+        assert_eq!(caesar.explain_encrypt(text).output(), caesar.encrypt(text));
+        assert_eq!(caesar.explain_decrypt(text).output(), caesar.decrypt(text));
+    }
+
+    #[test]
+    fn decrypt_steps() {
+        let explanation = Caesar::new(3).explain_decrypt("a\n");
+
+        assert_eq!(
+            explanation.steps(),
+            [
+                Step {
+                    original: 'a',
+                    position: Some(0),
+                    shift: -3,
+                    result: 'x',
+                },
+                Step {
+                    original: '\n',
+                    position: None,
+                    shift: 0,
+                    result: '\n',
+                },
+            ]
+        );
+        assert_eq!(
+            explanation.to_string(),
+            "'a'  0  -3 -> 'x' 23\n'\\n' unchanged\n"
+        );
+    }
+}
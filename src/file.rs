@@ -0,0 +1,235 @@
+use crate::Caesar;
+use std::fs::File;
+use std::io::{self, BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+// large enough to amortize syscalls, small enough to live anywhere
+const BUFFER_SIZE: usize = 64 * 1024;
+
+impl Caesar {
+    /// Encrypts everything read from `reader` and writes it to `writer`,
+    /// streaming through a fixed-size buffer so inputs larger than memory
+    /// are fine. Returns the number of bytes processed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut output = Vec::new();
+    /// c.encrypt_stream("Attack at dawn!".as_bytes(), &mut output).unwrap();
+    /// assert_eq!(output, b"Cvvcem cv fcyp!");
+    /// ```
+    pub fn encrypt_stream<R: Read, W: Write>(&self, reader: R, writer: W) -> io::Result<u64> {
+        stream(reader, writer, |chunk| self.encrypt_bytes(chunk), |_| {})
+    }
+
+    /// Like [`encrypt_stream`](Caesar::encrypt_stream), but calls
+    /// `progress` with the number of bytes processed so far after each
+    /// chunk is written, for drawing progress bars.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let mut done = 0;
+    /// let mut output = Vec::new();
+    /// Caesar::new(2)
+    ///     .encrypt_stream_with_progress("Attack at dawn!".as_bytes(), &mut output, |n| done = n)
+    ///     .unwrap();
+    /// assert_eq!(done, 15);
+    /// ```
+    pub fn encrypt_stream_with_progress<R, W, P>(
+        &self,
+        reader: R,
+        writer: W,
+        progress: P,
+    ) -> io::Result<u64>
+    where
+        R: Read,
+        W: Write,
+        P: FnMut(u64),
+    {
+        stream(reader, writer, |chunk| self.encrypt_bytes(chunk), progress)
+    }
+
+    /// Decrypts everything read from `reader` and writes it to `writer`,
+    /// streaming through a fixed-size buffer so inputs larger than memory
+    /// are fine. Returns the number of bytes processed.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut output = Vec::new();
+    /// c.decrypt_stream("Cvvcem cv fcyp!".as_bytes(), &mut output).unwrap();
+    /// assert_eq!(output, b"Attack at dawn!");
+    /// ```
+    pub fn decrypt_stream<R: Read, W: Write>(&self, reader: R, writer: W) -> io::Result<u64> {
+        stream(reader, writer, |chunk| self.decrypt_bytes(chunk), |_| {})
+    }
+
+    /// Like [`decrypt_stream`](Caesar::decrypt_stream), but calls
+    /// `progress` with the number of bytes processed so far after each
+    /// chunk is written.
+    pub fn decrypt_stream_with_progress<R, W, P>(
+        &self,
+        reader: R,
+        writer: W,
+        progress: P,
+    ) -> io::Result<u64>
+    where
+        R: Read,
+        W: Write,
+        P: FnMut(u64),
+    {
+        stream(reader, writer, |chunk| self.decrypt_bytes(chunk), progress)
+    }
+
+    /// Encrypts the file at `src` into a new file at `dst`, replacing it if
+    /// it exists. Returns the number of bytes processed.
+    ///
+    /// `src` is opened before `dst` is touched, so a missing source leaves
+    /// `dst` alone, and the two being the same file fails with
+    /// [`ErrorKind::InvalidInput`] rather than truncating it.
+    pub fn encrypt_file<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<u64> {
+        let (reader, writer) = open_pair(src.as_ref(), dst.as_ref())?;
+        self.encrypt_stream(reader, writer)
+    }
+
+    /// Decrypts the file at `src` into a new file at `dst`, replacing it if
+    /// it exists. Returns the number of bytes processed. Fails the same
+    /// way as [`encrypt_file`](Caesar::encrypt_file) if `src` is missing
+    /// or is the same file as `dst`.
+    pub fn decrypt_file<P: AsRef<Path>, Q: AsRef<Path>>(&self, src: P, dst: Q) -> io::Result<u64> {
+        let (reader, writer) = open_pair(src.as_ref(), dst.as_ref())?;
+        self.decrypt_stream(reader, writer)
+    }
+}
+
+// opens src, then creates dst, refusing to truncate src by writing over it
+fn open_pair(src: &Path, dst: &Path) -> io::Result<(File, BufWriter<File>)> {
+    let reader = File::open(src)?;
+
+    if let Ok(dst) = dst.canonicalize() {
+        if dst == src.canonicalize()? {
+            return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "source and destination are the same file",
+            ));
+        }
+    }
+
+    Ok((reader, BufWriter::new(File::create(dst)?)))
+}
+
+fn stream<R, W, F, P>(mut reader: R, mut writer: W, f: F, mut progress: P) -> io::Result<u64>
+where
+    R: Read,
+    W: Write,
+    F: Fn(&mut [u8]),
+    P: FnMut(u64),
+{
+    let mut buf = vec![0; BUFFER_SIZE];
+    let mut total = 0;
+
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+
+        f(&mut buf[..n]);
+        writer.write_all(&buf[..n])?;
+        total += n as u64;
+        progress(total);
+    }
+
+    writer.flush()?;
+    Ok(total)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn stream_larger_than_buffer() {
+        let caesar = Caesar::new(8);
+        let input = "Streaming keeps memory bounded. ".repeat(5000);
+
+        let mut encrypted = Vec::new();
+        let n = caesar
+            .encrypt_stream(input.as_bytes(), &mut encrypted)
+            .unwrap();
+
+        assert_eq!(n, input.len() as u64);
+        assert_eq!(encrypted, caesar.encrypt(&input).as_bytes());
+    }
+
+    #[test]
+    fn progress_per_chunk() {
+        let caesar = Caesar::new(8);
+        let input = vec![b'a'; BUFFER_SIZE * 2 + 10];
+
+        let mut reports = Vec::new();
+        caesar
+            .decrypt_stream_with_progress(&input[..], io::sink(), |n| reports.push(n))
+            .unwrap();
+
+        let size = BUFFER_SIZE as u64;
+        assert_eq!(reports, [size, size * 2, size * 2 + 10]);
+    }
+
+    #[test]
+    fn file_roundtrip() {
+        let caesar = Caesar::new(21);
+        let dir = std::env::temp_dir();
+        let plain = dir.join(format!("csr-file-plain-{}", std::process::id()));
+        let cipher = dir.join(format!("csr-file-cipher-{}", std::process::id()));
+        let back = dir.join(format!("csr-file-back-{}", std::process::id()));
+
+        fs::write(&plain, "Files larger than RAM, one call.").unwrap();
+        caesar.encrypt_file(&plain, &cipher).unwrap();
+        caesar.decrypt_file(&cipher, &back).unwrap();
+
+        let encrypted = fs::read_to_string(&cipher).unwrap();
+        let decrypted = fs::read_to_string(&back).unwrap();
+
+        for path in [&plain, &cipher, &back].iter() {
+            fs::remove_file(path).unwrap();
+        }
+
+        assert_eq!(
+            encrypted,
+            caesar.encrypt("Files larger than RAM, one call.")
+        );
+        assert_eq!(decrypted, "Files larger than RAM, one call.");
+    }
+
+    #[test]
+    fn file_errors_keep_data() {
+        let caesar = Caesar::new(3);
+        let dir = std::env::temp_dir();
+        let missing = dir.join(format!("csr-file-missing-{}", std::process::id()));
+        let precious = dir.join(format!("csr-file-precious-{}", std::process::id()));
+
+        fs::write(&precious, "precious").unwrap();
+
+        let missing_src = caesar.encrypt_file(&missing, &precious).unwrap_err();
+        let same_file = caesar.decrypt_file(&precious, &precious).unwrap_err();
+        let contents = fs::read_to_string(&precious).unwrap();
+        fs::remove_file(&precious).unwrap();
+
+        assert_eq!(missing_src.kind(), ErrorKind::NotFound);
+        assert_eq!(same_file.kind(), ErrorKind::InvalidInput);
+        assert_eq!(contents, "precious");
+    }
+}
@@ -0,0 +1,228 @@
+//! A shared abstraction for shift ciphers whose shift can change from
+//! letter to letter.
+//!
+//! A [`Keystream`] yields the shift for each letter in turn, and can be
+//! fed back the plaintext as it goes. A Caesar is the simplest keystream,
+//! yielding the same shift forever, but custom keystreams can be plugged
+//! into [`encrypt_bytes`] and [`decrypt_bytes`] just the same.
+//!
+//! # Example
+//!
+//! ```
+//! use csr::keystream::{self, Keystream};
+//!
+//! // shifts the first letter by 0, the second by 1, and so on
+//! struct Progressive(u8);
+//!
+//! impl Keystream for Progressive {
+//!     fn next_shift(&mut self) -> u8 {
+//!         let shift = self.0;
+//!         self.0 = (self.0 + 1) % 26;
+//!         shift
+//!     }
+//! }
+//!
+//! let mut input = *b"aaa, aaa";
+//! keystream::encrypt_bytes(&mut Progressive(0), &mut input);
+//! assert_eq!(&input, b"abc, def");
+//!
+//! keystream::decrypt_bytes(&mut Progressive(0), &mut input);
+//! assert_eq!(&input, b"aaa, aaa");
+//! ```
+
+use crate::Caesar;
+
+/// A source of shifts, one per letter.
+pub trait Keystream {
+    /// Returns the shift to apply to the next letter. Shifts of 26 or more
+    /// are reduced.
+    fn next_shift(&mut self) -> u8;
+
+    /// Feeds back the plaintext letter that was just processed, as its
+    /// position in the alphabet (0 for `a`, 25 for `z`). Keystreams that
+    /// depend on the plaintext, like autokey, can use this; by default it
+    /// does nothing.
+    fn feed(&mut self, _plain: u8) {}
+}
+
+impl Keystream for Caesar {
+    fn next_shift(&mut self) -> u8 {
+        self.shift
+    }
+}
+
+impl<K: Keystream + ?Sized> Keystream for &mut K {
+    fn next_shift(&mut self) -> u8 {
+        (**self).next_shift()
+    }
+
+    fn feed(&mut self, plain: u8) {
+        (**self).feed(plain)
+    }
+}
+
+/// Encrypts a mutable slice of bytes in place with the given keystream.
+/// Only ASCII letters consume a shift; everything else is left untouched.
+pub fn encrypt_bytes<K: Keystream>(keystream: &mut K, chars: &mut [u8]) {
+    for c in chars {
+        if c.is_ascii_alphabetic() {
+            let plain = *c;
+            *c = encrypt_letter(plain, keystream.next_shift());
+            keystream.feed(plain.to_ascii_lowercase() - b'a');
+        }
+    }
+}
+
+/// Decrypts a mutable slice of bytes in place with the given keystream.
+/// Only ASCII letters consume a shift; everything else is left untouched.
+pub fn decrypt_bytes<K: Keystream>(keystream: &mut K, chars: &mut [u8]) {
+    for c in chars {
+        if c.is_ascii_alphabetic() {
+            *c = decrypt_letter(*c, keystream.next_shift());
+            keystream.feed(c.to_ascii_lowercase() - b'a');
+        }
+    }
+}
+
+/// A stateful encryptor or decryptor for chunked input. The keystream's
+/// position is kept between calls to [`update`](StreamCipher::update), so
+/// splitting a message into chunks (network frames, file blocks) gives the
+/// same output as processing it all at once.
+///
+/// # Example
+///
+/// ```
+/// use csr::keystream::StreamCipher;
+/// use csr::Caesar;
+///
+/// let mut stream = StreamCipher::encryptor(Caesar::new(2));
+/// let mut first = *b"Attack ";
+/// let mut second = *b"at dawn!";
+/// stream.update(&mut first);
+/// stream.update(&mut second);
+/// assert_eq!(&first, b"Cvvcem ");
+/// assert_eq!(&second, b"cv fcyp!");
+/// ```
+#[derive(Debug, Clone)]
+pub struct StreamCipher<K> {
+    keystream: K,
+    decrypt: bool,
+}
+
+impl<K: Keystream> StreamCipher<K> {
+    /// Constructs a stream that encrypts each chunk passed to it.
+    pub fn encryptor(keystream: K) -> Self {
+        StreamCipher {
+            keystream,
+            decrypt: false,
+        }
+    }
+
+    /// Constructs a stream that decrypts each chunk passed to it.
+    pub fn decryptor(keystream: K) -> Self {
+        StreamCipher {
+            keystream,
+            decrypt: true,
+        }
+    }
+
+    /// Transforms the next chunk in place, continuing from where the
+    /// previous chunk left off.
+    pub fn update(&mut self, chunk: &mut [u8]) {
+        if self.decrypt {
+            decrypt_bytes(&mut self.keystream, chunk)
+        } else {
+            encrypt_bytes(&mut self.keystream, chunk)
+        }
+    }
+
+    /// Returns the keystream, in whatever state the stream left it.
+    pub fn into_inner(self) -> K {
+        self.keystream
+    }
+}
+
+// shifts an ASCII letter forward, leaving any other byte unchanged
+pub(crate) fn encrypt_letter(c: u8, shift: u8) -> u8 {
+    let shift = shift % 26;
+
+    match c {
+        // this is first because most letters will be lowercase
+        // a-z lowercase
+        b'a'..=b'z' => b'a' + (c - b'a' + shift) % 26,
+        // A-Z uppercase
+        b'A'..=b'Z' => b'A' + (c - b'A' + shift) % 26,
+        _ => c,
+    }
+}
+
+// shifts an ASCII letter backward, leaving any other byte unchanged
+pub(crate) fn decrypt_letter(c: u8, shift: u8) -> u8 {
+    encrypt_letter(c, 26 - shift % 26)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a keystream that shifts each letter by the previous plaintext letter
+    struct Autokey(u8);
+
+    impl Keystream for Autokey {
+        fn next_shift(&mut self) -> u8 {
+            self.0
+        }
+
+        fn feed(&mut self, plain: u8) {
+            self.0 = plain;
+        }
+    }
+
+    #[test]
+    fn caesar_keystream_matches_caesar() {
+        let caesar = Caesar::new(17);
+        let input = "Keystreams keep the family consistent!";
+
+        let mut bytes = input.as_bytes().to_vec();
+        encrypt_bytes(&mut Caesar::new(17), &mut bytes);
+
+        assert_eq!(bytes, caesar.encrypt(input).as_bytes());
+    }
+
+    #[test]
+    fn plaintext_feedback_roundtrip() {
+        let input = "Attack at dawn!";
+
+        let mut bytes = input.as_bytes().to_vec();
+        encrypt_bytes(&mut Autokey(3), &mut bytes);
+
+        assert_eq!(bytes, b"Dtmtcm kt wdwj!");
+
+        decrypt_bytes(&mut Autokey(3), &mut bytes);
+
+        assert_eq!(bytes, input.as_bytes());
+    }
+
+    #[test]
+    fn chunks_keep_alignment() {
+        let input = b"Chunk boundaries must not matter, even mid-word.";
+
+        let mut whole = input.to_vec();
+        encrypt_bytes(&mut Autokey(7), &mut whole);
+
+        let mut chunked = input.to_vec();
+        let mut stream = StreamCipher::encryptor(Autokey(7));
+        for chunk in chunked.chunks_mut(5) {
+            stream.update(chunk);
+        }
+
+        assert_eq!(chunked, whole);
+
+        let mut stream = StreamCipher::decryptor(Autokey(7));
+        for chunk in chunked.chunks_mut(3) {
+            stream.update(chunk);
+        }
+
+        assert_eq!(&chunked[..], &input[..]);
+    }
+}
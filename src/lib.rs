@@ -1,44 +1,208 @@
+#![forbid(unsafe_code)]
+
+mod armor;
+mod branchless;
+mod builder;
+mod cipher;
+pub mod codec;
+mod crack;
+#[cfg(feature = "csv")]
+mod csv;
+mod display;
+mod envelope;
+mod error;
+mod explain;
+mod file;
+pub mod keystream;
+mod modular;
+mod obfuscate;
+mod parallel;
+mod path;
+mod pipeline;
+mod report;
+mod rotation;
+mod table;
+mod tree;
+
+pub use armor::Armor;
+pub use builder::CaesarBuilder;
+pub use cipher::Cipher;
+pub use crack::{crack, Candidate};
+/// Derives [`Obfuscate`] for a struct, encrypting every `String` field
+/// except those marked `#[csr(skip)]`.
+///
+/// Any other `csr` attribute is a compile error, so a typo can't quietly
+/// obfuscate a field that was meant to be skipped:
+///
+/// ```compile_fail
+/// use csr::CaesarObfuscate;
+///
+/// #[derive(CaesarObfuscate)]
+/// struct Record {
+///     #[csr(skp)]
+///     id: String,
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use csr_derive::CaesarObfuscate;
+#[cfg(feature = "csv")]
+pub use csv::{Column, CsvOptions};
+pub use display::{Encrypted, EncryptingWriter};
+pub use envelope::Envelope;
+pub use error::Error;
+pub use explain::{Explanation, Step};
+pub use keystream::Keystream;
+pub use modular::{shift_mod, unshift_mod};
+pub use obfuscate::Obfuscate;
+pub use parallel::DEFAULT_PARALLEL_THRESHOLD;
+pub use pipeline::Pipeline;
+pub use report::{Guess, Report};
+pub use rotation::Rotating;
+pub use table::{tabula_recta, CaesarTable};
+pub use tree::{TreeEntry, TreeOptions};
+
 use num::cast::AsPrimitive;
-use std::ops::{Deref, Rem};
+use num::Zero;
+use std::borrow::Cow;
+use std::io::{self, BufRead};
+use std::ops::{Add, Sub};
+use std::str::FromStr;
 
 /// The main type of this crate. Holds a key (u8), and provides the methods
 /// to encrypt and decrypt Strings, slices, and more!
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Caesar {
     shift: u8,
 }
 
 impl Caesar {
     /// Constructs a new Caesar with the provided shift. If the shift
-    /// isn't valid, this function will get the remainder and shift by
-    /// that instead.
+    /// isn't valid, this function will reduce it modulo 26 and shift by
+    /// that instead. The full value is reduced, so wide types aren't
+    /// truncated first, and negative shifts count backwards.
     ///
     /// # Examples
     ///
     /// ```
     /// use csr::Caesar;
     ///
-    /// // value is in between 0 and 26 so it is ok!
+    /// // value is in between 0 and 25 so it is ok!
     /// let c = Caesar::new(2);
     /// ```
     ///
     /// ```
     /// use csr::Caesar;
     ///
-    /// // gets remainder, returning 22
-    /// let c = Caesar::new(100);
+    /// // gets remainder, returning 14
+    /// let c = Caesar::new(300u32);
+    /// assert_eq!(c.shift(), 14);
+    ///
+    /// // counts backwards, returning 25
+    /// let c = Caesar::new(-1);
+    /// assert_eq!(c.shift(), 25);
     /// ```
-    pub fn new<U: AsPrimitive<u8> + Rem>(shift: U) -> Self {
-        // shift size should be bigger than 0 and smaller than or equal to 26
+    pub fn new<U>(shift: U) -> Self
+    where
+        U: AsPrimitive<i128> + AsPrimitive<u128> + PartialOrd + Zero,
+    {
         Caesar {
-            shift: match shift.as_() {
-                0..=26 => shift.as_(),
-                _ => shift.as_() % 26,
-            },
+            shift: reduce(shift).0,
         }
     }
 
-    /// Encrypts a buffer and consumes the Caesar.
+    /// Deterministically derives a Caesar from a passphrase, so people can
+    /// share a memorable phrase instead of a number.
+    ///
+    /// The shift is the 32-bit FNV-1a hash of the passphrase's UTF-8 bytes,
+    /// reduced to the range 1 to 25 (`hash % 25 + 1`), so a passphrase
+    /// never derives the identity shift. This algorithm is part of the
+    /// public API and will not change between versions.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::from_passphrase("open sesame");
+    /// assert_eq!(c.shift(), 7);
+    /// ```
+    pub fn from_passphrase<S: AsRef<str>>(passphrase: S) -> Self {
+        let hash = passphrase.as_ref().bytes().fold(0x811c_9dc5u32, |hash, b| {
+            (hash ^ u32::from(b)).wrapping_mul(0x0100_0193)
+        });
+
+        Caesar::new((hash % 25 + 1) as u8)
+    }
+
+    /// Returns the shift of this Caesar, after it was normalized by
+    /// [`new`](Caesar::new).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// assert_eq!(Caesar::new(2).shift(), 2);
+    /// assert_eq!(Caesar::new(100).shift(), 22);
+    /// ```
+    pub fn shift(&self) -> u8 {
+        self.shift
+    }
+
+    /// Returns the substitution alphabet for this shift: the letter at index
+    /// `n` is what the `n`th lowercase letter encrypts to. Uppercase letters
+    /// map the same way.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let mapping = Caesar::new(3).mapping();
+    /// assert_eq!(mapping[0], 'd');
+    /// assert_eq!(mapping[25], 'c');
+    /// ```
+    pub fn mapping(&self) -> [char; 26] {
+        let mut mapping = ['a'; 26];
+
+        for (c, m) in (b'a'..=b'z').zip(mapping.iter_mut()) {
+            *m = self.encrypt_char(c as char);
+        }
+
+        mapping
+    }
+
+    /// Returns a [`CaesarBuilder`] for configuring a Caesar step by step.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::builder().shift(2).build().unwrap();
+    /// assert_eq!(c.encrypt("Hello world!"), "Jgnnq yqtnf!");
+    /// ```
+    pub fn builder() -> CaesarBuilder {
+        CaesarBuilder::new()
+    }
+
+    /// Returns the Caesar that undoes this one, so that encrypting with the
+    /// inverse is the same as decrypting with the original.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(3);
+    /// let input = "Attack at dawn!";
+    /// assert_eq!(c.inverse().encrypt(input), c.decrypt(input));
+    /// ```
+    pub fn inverse(&self) -> Self {
+        Caesar::new(26 - self.shift)
+    }
+
+    /// Encrypts a buffer.
     ///
     /// # Example
     ///
@@ -49,34 +213,184 @@ impl Caesar {
     /// let input = "Attack at dawn!";
     /// assert_eq!(c.encrypt(input), "Cvvcem cv fcyp!")
     /// ```
-    pub fn encrypt<S: Deref<Target = str>>(self, buf: S) -> String {
-        let chars = buf.as_bytes();
-
-        let vec: Vec<u8> = chars
-            .iter()
-            .map(|c| match c {
-                // this is first because most letters will be lowercase
-                // a-z lowercase
-                b'a'..=b'z' => {
-                    let pos = c % 97;
-                    97 + ((pos + self.shift) % 26)
-                }
-                // A-Z uppercase
-                b'A'..=b'Z' => {
-                    let pos = c % 65;
-                    65 + ((pos + self.shift) % 26)
-                }
-                _ => *c,
-            })
-            .collect();
-
-        // this is safe because non-utf8 bytes will never be passed
-        // thanks to the trait bound.
-        unsafe { String::from_utf8_unchecked(vec) }
+    pub fn encrypt<S: AsRef<str>>(&self, buf: S) -> String {
+        let mut vec = buf.as_ref().as_bytes().to_vec();
+        self.encrypt_bytes(&mut vec);
+
+        // the input was valid UTF-8 thanks to the trait bound, and only
+        // ASCII letters were replaced with other ASCII letters.
+        String::from_utf8(vec).expect("output is valid UTF-8")
+    }
+
+    /// Encrypts a string, borrowing it instead of allocating when the output
+    /// would be identical to the input (no ASCII letters, or a shift of 0).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    /// use std::borrow::Cow;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert!(matches!(c.encrypt_cow("1234 😀"), Cow::Borrowed(_)));
+    /// assert!(matches!(c.encrypt_cow("abcd"), Cow::Owned(_)));
+    /// ```
+    pub fn encrypt_cow<'a>(&self, buf: &'a str) -> Cow<'a, str> {
+        if self.is_identity_for(buf) {
+            Cow::Borrowed(buf)
+        } else {
+            Cow::Owned(self.encrypt(buf))
+        }
+    }
+
+    /// Returns an adapter that encrypts `buf` lazily when it is formatted,
+    /// without allocating. See [`Encrypted`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert_eq!(format!("{}", c.display("Hello world!")), "Jgnnq yqtnf!");
+    /// ```
+    pub fn display<'a>(&self, buf: &'a str) -> Encrypted<'a> {
+        Encrypted::new(*self, buf)
+    }
+
+    /// Encrypts a string in place, without allocating a second copy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut input = String::from("Attack at dawn!");
+    /// c.encrypt_in_place(&mut input);
+    /// assert_eq!(input, "Cvvcem cv fcyp!")
+    /// ```
+    pub fn encrypt_in_place(&self, buf: &mut String) {
+        let mut vec = std::mem::take(buf).into_bytes();
+        self.encrypt_bytes(&mut vec);
+
+        // only ASCII letters are changed, and they are always replaced
+        // with other ASCII letters, so this never reallocates or fails.
+        *buf = String::from_utf8(vec).expect("output is valid UTF-8");
+    }
+
+    /// Encrypts a string into a caller-provided buffer, appending the
+    /// output to `dst`. This lets hot loops reuse one buffer instead of
+    /// allocating a new String per message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut buf = Vec::new();
+    /// c.encrypt_into("Attack at dawn!", &mut buf);
+    /// assert_eq!(buf, b"Cvvcem cv fcyp!");
+    /// ```
+    pub fn encrypt_into<S: AsRef<str>>(&self, src: S, dst: &mut Vec<u8>) {
+        let src = src.as_ref();
+        let start = dst.len();
+        dst.extend_from_slice(src.as_bytes());
+        self.encrypt_bytes(&mut dst[start..]);
+    }
+
+    /// Encrypts a string into a caller-provided slice, returning the number
+    /// of bytes written. If `dst` is shorter than `src`, only the bytes
+    /// that fit are written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut buf = [0; 32];
+    /// let written = c.encrypt_into_slice("Attack at dawn!", &mut buf);
+    /// assert_eq!(&buf[..written], b"Cvvcem cv fcyp!");
+    /// ```
+    pub fn encrypt_into_slice<S: AsRef<str>>(&self, src: S, dst: &mut [u8]) -> usize {
+        let src = src.as_ref();
+        let len = src.len().min(dst.len());
+        dst[..len].copy_from_slice(&src.as_bytes()[..len]);
+        self.encrypt_bytes(&mut dst[..len]);
+        len
+    }
+
+    /// Encrypts every string in a collection, returning the results in
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert_eq!(c.encrypt_all(vec!["Attack", "dawn"]), ["Cvvcem", "fcyp"]);
+    /// ```
+    pub fn encrypt_all<I>(&self, iter: I) -> Vec<String>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.encrypt_iter(iter).collect()
+    }
+
+    /// Lazily encrypts every string yielded by an iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut iter = c.encrypt_iter(["Attack", "dawn"].iter());
+    /// assert_eq!(iter.next().unwrap(), "Cvvcem");
+    /// ```
+    pub fn encrypt_iter<I>(&self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let caesar = *self;
+        iter.into_iter().map(move |s| caesar.encrypt(s))
+    }
+
+    /// Lazily encrypts each line read from `reader`, so large inputs never
+    /// have to be loaded into memory at once. Lines are yielded without
+    /// their line endings, like [`BufRead::lines`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let input = "Attack at dawn!\nAttack at dawn!\n".as_bytes();
+    /// for line in c.encrypt_lines(input) {
+    ///     assert_eq!(line.unwrap(), "Cvvcem cv fcyp!");
+    /// }
+    /// ```
+    pub fn encrypt_lines<R: BufRead>(&self, reader: R) -> impl Iterator<Item = io::Result<String>> {
+        let caesar = *self;
+        reader
+            .lines()
+            .map(move |line| line.map(|line| caesar.encrypt(line)))
     }
 
     /// This function takes a mutable slice of bytes and encrypts them in place.
     ///
+    /// Only ASCII letters are changed and every other byte is left alone,
+    /// so text in any ASCII-compatible encoding, like Latin-1 or
+    /// Windows-1252, can be transformed as raw bytes without decoding it
+    /// first. Accented letters outside ASCII are passed through, the same
+    /// as they are for strings.
+    ///
     /// # Safety
     ///
     /// This function is safe because it only guarantees valid UTF-8 bytes
@@ -95,26 +409,32 @@ impl Caesar {
     /// c.encrypt_bytes(&mut bytes);
     /// assert_eq!(bytes, output);
     /// ```
-    pub fn encrypt_bytes(self, chars: &mut [u8]) {
-        for c in chars {
-            *c = match *c {
-                // this is first because most letters will be lowercase
-                // a-z lowercase
-                b'a'..=b'z' => {
-                    let pos = *c % 97;
-                    97 + ((pos + self.shift) % 26)
-                }
-                // A-Z uppercase
-                b'A'..=b'Z' => {
-                    let pos = *c % 65;
-                    65 + ((pos + self.shift) % 26)
-                }
-                _ => *c,
-            }
+    pub fn encrypt_bytes(&self, chars: &mut [u8]) {
+        branchless::encrypt_bytes(self.shift, chars);
+    }
+
+    /// Encrypts a single character. Useful for transforming keystrokes one
+    /// at a time without allocating.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert_eq!(c.encrypt_char('a'), 'c');
+    /// assert_eq!(c.encrypt_char('Z'), 'B');
+    /// assert_eq!(c.encrypt_char('!'), '!');
+    /// ```
+    pub fn encrypt_char(&self, c: char) -> char {
+        if c.is_ascii_alphabetic() {
+            keystream::encrypt_letter(c as u8, self.shift) as char
+        } else {
+            c
         }
     }
 
-    /// Decrypts a buffer and consumes the Caesar.
+    /// Decrypts a buffer.
     ///
     /// # Example
     ///
@@ -125,34 +445,167 @@ impl Caesar {
     /// let input = "They are coming from the north!";
     /// assert_eq!(c.encrypt(input), "Vjga ctg eqokpi htqo vjg pqtvj!")
     /// ```
-    pub fn decrypt<S: Deref<Target = str>>(self, buf: S) -> String {
-        let chars = buf.as_bytes();
-
-        let vec: Vec<u8> = chars
-            .iter()
-            .map(|c| match c {
-                // this is first because most letters will be lowercase
-                // a-z lowercase
-                b'a'..=b'z' => {
-                    let pos = c % 97;
-                    122 - (((25 - pos) + self.shift) % 26)
-                }
-                // A-Z uppercase
-                b'A'..=b'Z' => {
-                    let pos = c % 65;
-                    90 - (((25 - pos) + self.shift) % 26)
-                }
-                _ => *c,
-            })
-            .collect();
-
-        // this is safe because non-utf8 bytes will never be passed
-        // thanks to the trait bound.
-        unsafe { String::from_utf8_unchecked(vec) }
+    pub fn decrypt<S: AsRef<str>>(&self, buf: S) -> String {
+        let mut vec = buf.as_ref().as_bytes().to_vec();
+        self.decrypt_bytes(&mut vec);
+
+        // the input was valid UTF-8 thanks to the trait bound, and only
+        // ASCII letters were replaced with other ASCII letters.
+        String::from_utf8(vec).expect("output is valid UTF-8")
+    }
+
+    /// Decrypts a string, borrowing it instead of allocating when the output
+    /// would be identical to the input (no ASCII letters, or a shift of 0).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    /// use std::borrow::Cow;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert!(matches!(c.decrypt_cow("1234 😀"), Cow::Borrowed(_)));
+    /// assert!(matches!(c.decrypt_cow("abcd"), Cow::Owned(_)));
+    /// ```
+    pub fn decrypt_cow<'a>(&self, buf: &'a str) -> Cow<'a, str> {
+        if self.is_identity_for(buf) {
+            Cow::Borrowed(buf)
+        } else {
+            Cow::Owned(self.decrypt(buf))
+        }
+    }
+
+    /// Decrypts a string in place, without allocating a second copy.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut input = String::from("Cvvcem cv fcyp!");
+    /// c.decrypt_in_place(&mut input);
+    /// assert_eq!(input, "Attack at dawn!")
+    /// ```
+    pub fn decrypt_in_place(&self, buf: &mut String) {
+        let mut vec = std::mem::take(buf).into_bytes();
+        self.decrypt_bytes(&mut vec);
+
+        // only ASCII letters are changed, and they are always replaced
+        // with other ASCII letters, so this never reallocates or fails.
+        *buf = String::from_utf8(vec).expect("output is valid UTF-8");
+    }
+
+    /// Decrypts a string into a caller-provided buffer, appending the
+    /// output to `dst`. This lets hot loops reuse one buffer instead of
+    /// allocating a new String per message.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut buf = Vec::new();
+    /// c.decrypt_into("Cvvcem cv fcyp!", &mut buf);
+    /// assert_eq!(buf, b"Attack at dawn!");
+    /// ```
+    pub fn decrypt_into<S: AsRef<str>>(&self, src: S, dst: &mut Vec<u8>) {
+        let src = src.as_ref();
+        let start = dst.len();
+        dst.extend_from_slice(src.as_bytes());
+        self.decrypt_bytes(&mut dst[start..]);
+    }
+
+    /// Decrypts a string into a caller-provided slice, returning the number
+    /// of bytes written. If `dst` is shorter than `src`, only the bytes
+    /// that fit are written.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut buf = [0; 32];
+    /// let written = c.decrypt_into_slice("Cvvcem cv fcyp!", &mut buf);
+    /// assert_eq!(&buf[..written], b"Attack at dawn!");
+    /// ```
+    pub fn decrypt_into_slice<S: AsRef<str>>(&self, src: S, dst: &mut [u8]) -> usize {
+        let src = src.as_ref();
+        let len = src.len().min(dst.len());
+        dst[..len].copy_from_slice(&src.as_bytes()[..len]);
+        self.decrypt_bytes(&mut dst[..len]);
+        len
+    }
+
+    /// Decrypts every string in a collection, returning the results in
+    /// order.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert_eq!(c.decrypt_all(vec!["Cvvcem", "fcyp"]), ["Attack", "dawn"]);
+    /// ```
+    pub fn decrypt_all<I>(&self, iter: I) -> Vec<String>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        self.decrypt_iter(iter).collect()
+    }
+
+    /// Lazily decrypts every string yielded by an iterator.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut iter = c.decrypt_iter(["Cvvcem", "fcyp"].iter());
+    /// assert_eq!(iter.next().unwrap(), "Attack");
+    /// ```
+    pub fn decrypt_iter<I>(&self, iter: I) -> impl Iterator<Item = String>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<str>,
+    {
+        let caesar = *self;
+        iter.into_iter().map(move |s| caesar.decrypt(s))
+    }
+
+    /// Lazily decrypts each line read from `reader`, so large inputs never
+    /// have to be loaded into memory at once. Lines are yielded without
+    /// their line endings, like [`BufRead::lines`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// let input = "Cvvcem cv fcyp!\nCvvcem cv fcyp!\n".as_bytes();
+    /// for line in c.decrypt_lines(input) {
+    ///     assert_eq!(line.unwrap(), "Attack at dawn!");
+    /// }
+    /// ```
+    pub fn decrypt_lines<R: BufRead>(&self, reader: R) -> impl Iterator<Item = io::Result<String>> {
+        let caesar = *self;
+        reader
+            .lines()
+            .map(move |line| line.map(|line| caesar.decrypt(line)))
     }
 
     /// This function takes a mutable slice of bytes and decrypts them in place.
     ///
+    /// Like [`encrypt_bytes`](Caesar::encrypt_bytes), only ASCII letters are
+    /// changed, so Latin-1, Windows-1252 and other ASCII-compatible text can
+    /// be decrypted as raw bytes without decoding it first.
+    ///
     /// # Safety
     ///
     /// This function is safe because it only guarantees valid UTF-8 bytes
@@ -171,23 +624,127 @@ impl Caesar {
     /// c.decrypt_bytes(&mut bytes);
     /// assert_eq!(bytes, output);
     /// ```
-    pub fn decrypt_bytes(self, chars: &mut [u8]) {
-        for c in chars {
-            *c = match *c {
-                // this is first because most letters will be lowercase
-                // a-z lowercase
-                b'a'..=b'z' => {
-                    let pos = *c % 97;
-                    122 - (((25 - pos) + self.shift) % 26)
-                }
-                // A-Z uppercase
-                b'A'..=b'Z' => {
-                    let pos = *c % 65;
-                    90 - (((25 - pos) + self.shift) % 26)
-                }
-                _ => *c,
+    pub fn decrypt_bytes(&self, chars: &mut [u8]) {
+        self.inverse().encrypt_bytes(chars)
+    }
+
+    /// Decrypts a single character.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert_eq!(c.decrypt_char('c'), 'a');
+    /// assert_eq!(c.decrypt_char('B'), 'Z');
+    /// assert_eq!(c.decrypt_char('!'), '!');
+    /// ```
+    pub fn decrypt_char(&self, c: char) -> char {
+        if c.is_ascii_alphabetic() {
+            keystream::decrypt_letter(c as u8, self.shift) as char
+        } else {
+            c
+        }
+    }
+
+    // returns true if transforming buf would leave it unchanged
+    fn is_identity_for(&self, buf: &str) -> bool {
+        self.shift == 0 || !buf.bytes().any(|c| c.is_ascii_alphabetic())
+    }
+}
+
+/// Composes two shifts, so that encrypting with `a + b` is the same as
+/// encrypting with `a` and then with `b`.
+///
+/// # Example
+///
+/// ```
+/// use csr::Caesar;
+///
+/// assert_eq!(Caesar::new(3) + Caesar::new(5), Caesar::new(8));
+/// ```
+impl Add for Caesar {
+    type Output = Caesar;
+
+    fn add(self, rhs: Caesar) -> Caesar {
+        Caesar::new(self.shift + rhs.shift)
+    }
+}
+
+/// Composes a shift with the inverse of another, so that encrypting with
+/// `a - b` is the same as encrypting with `a` and then decrypting with `b`.
+///
+/// # Example
+///
+/// ```
+/// use csr::Caesar;
+///
+/// assert_eq!(Caesar::new(3) - Caesar::new(5), Caesar::new(24));
+/// ```
+impl Sub for Caesar {
+    type Output = Caesar;
+
+    fn sub(self, rhs: Caesar) -> Caesar {
+        self.add(rhs.inverse())
+    }
+}
+
+/// Parses a Caesar from a cipher spec. Accepted forms are a plain number
+/// (`"13"`), a signed number (`"+5"`, `"-3"`), a rot name (`"rot13"`), or a
+/// single letter giving the shift of `a` (`"d"` is a shift of 3).
+///
+/// # Example
+///
+/// ```
+/// use csr::Caesar;
+///
+/// let c: Caesar = "rot13".parse().unwrap();
+/// assert_eq!(c.encrypt("Hello"), "Uryyb");
+///
+/// let c: Caesar = "-3".parse().unwrap();
+/// assert_eq!(c.encrypt("d"), "a");
+///
+/// assert!("garbage".parse::<Caesar>().is_err());
+/// ```
+impl FromStr for Caesar {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let [c] = s.as_bytes() {
+            if c.is_ascii_alphabetic() {
+                return Ok(Caesar::new(c.to_ascii_lowercase() - b'a'));
             }
         }
+
+        let number = match s.get(..3) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("rot") => &s[3..],
+            _ => s,
+        };
+
+        number
+            .parse::<i64>()
+            .map(Caesar::new)
+            .map_err(|_| Error::InvalidSpec)
+    }
+}
+
+// reduces a shift of any primitive type modulo 26, also returning whether
+// it was already in 0 to 25. unsigned values above i128::MAX wrap negative
+// when cast, so those are reduced as u128 instead
+pub(crate) fn reduce<U>(shift: U) -> (u8, bool)
+where
+    U: AsPrimitive<i128> + AsPrimitive<u128> + PartialOrd + Zero,
+{
+    let signed: i128 = shift.as_();
+
+    if signed < 0 && shift > U::zero() {
+        let unsigned: u128 = shift.as_();
+        ((unsigned % 26) as u8, false)
+    } else {
+        (signed.rem_euclid(26) as u8, (0..26).contains(&signed))
     }
 }
 
@@ -269,4 +826,223 @@ mod tests {
 
         assert_eq!(caesar.encrypt(input), output);
     }
+
+    #[test]
+    fn char_roundtrip() {
+        let key: u8 = 7;
+        let caesar = Caesar::new(key);
+
+        let input = "Hello, world!";
+        let encrypted: String = input.chars().map(|c| caesar.encrypt_char(c)).collect();
+
+        assert_eq!(encrypted, caesar.encrypt(input));
+
+        let decrypted: String = encrypted.chars().map(|c| caesar.decrypt_char(c)).collect();
+
+        assert_eq!(decrypted, input);
+    }
+
+    #[test]
+    fn in_place_roundtrip() {
+        let key: u8 = 11;
+        let caesar = Caesar::new(key);
+
+        let input = "Mixed 🙂 content, with ÜTF-8!";
+        let mut buf = String::from(input);
+
+        caesar.encrypt_in_place(&mut buf);
+        assert_eq!(buf, caesar.encrypt(input));
+
+        caesar.decrypt_in_place(&mut buf);
+        assert_eq!(buf, input);
+    }
+
+    #[test]
+    fn into_reuses_buffer() {
+        let key: u8 = 4;
+        let caesar = Caesar::new(key);
+
+        let mut buf = Vec::new();
+        caesar.encrypt_into("First", &mut buf);
+        caesar.encrypt_into(" second", &mut buf);
+
+        assert_eq!(buf, caesar.encrypt("First second").as_bytes());
+
+        buf.clear();
+        caesar.decrypt_into("Jmvwx", &mut buf);
+
+        assert_eq!(buf, b"First");
+    }
+
+    #[test]
+    fn into_slice_truncates() {
+        let key: u8 = 1;
+        let caesar = Caesar::new(key);
+
+        let mut buf = [0; 4];
+        let written = caesar.encrypt_into_slice("abcdef", &mut buf);
+
+        assert_eq!(written, 4);
+        assert_eq!(&buf, b"bcde");
+    }
+
+    #[test]
+    fn cow_borrows_unchanged() {
+        let caesar = Caesar::new(26);
+
+        assert!(matches!(
+            caesar.encrypt_cow("Hello"),
+            Cow::Borrowed("Hello")
+        ));
+
+        let caesar = Caesar::new(3);
+        let input = "Khoor";
+
+        assert!(matches!(caesar.decrypt_cow("123"), Cow::Borrowed("123")));
+        assert_eq!(caesar.decrypt_cow(input), "Hello");
+    }
+
+    #[test]
+    fn parse_specs() {
+        let specs = ["13", "rot13", "ROT13", "+13", "-13", "n", " N "];
+
+        for spec in specs.iter() {
+            let caesar: Caesar = spec.parse().unwrap();
+            assert_eq!(caesar.encrypt("a"), "n", "spec {:?}", spec);
+        }
+
+        for spec in ["", "rot", "13x", "ab", "+", "1.5"].iter() {
+            assert_eq!(spec.parse::<Caesar>().err(), Some(Error::InvalidSpec));
+        }
+    }
+
+    #[test]
+    fn inverse_undoes() {
+        for key in 0..=26u8 {
+            let caesar = Caesar::new(key);
+            let input = "The quick brown fox jumps over the lazy dog";
+
+            assert_eq!(caesar.inverse().encrypt(caesar.encrypt(input)), input);
+            assert_eq!(
+                caesar.inverse().inverse().encrypt(input),
+                caesar.encrypt(input)
+            );
+        }
+    }
+
+    #[test]
+    fn compose_shifts() {
+        let input = "Composition is associative";
+        let (a, b, c) = (Caesar::new(7), Caesar::new(20), Caesar::new(25));
+
+        assert_eq!((a + b).encrypt(input), b.encrypt(a.encrypt(input)));
+        assert_eq!(((a + b) + c).encrypt(input), (a + (b + c)).encrypt(input));
+        assert_eq!((a - b).encrypt(input), b.decrypt(a.encrypt(input)));
+        assert_eq!((a - a).encrypt(input), input);
+    }
+
+    #[test]
+    fn usable_in_collections() {
+        use std::collections::HashSet;
+
+        let set: HashSet<Caesar> = (0..52u8).map(Caesar::new).collect();
+
+        assert_eq!(set.len(), 26);
+        assert!(set.contains(&Caesar::new(29)));
+        assert_eq!(format!("{:?}", Caesar::new(3)), "Caesar { shift: 3 }");
+    }
+
+    #[test]
+    fn string_like_inputs() {
+        let key: u8 = 5;
+        let caesar = Caesar::new(key);
+
+        let owned = String::from("Hello");
+        let boxed: Box<str> = "Hello".into();
+        let cow: Cow<str> = Cow::Borrowed("Hello");
+
+        assert_eq!(caesar.encrypt(&owned), "Mjqqt");
+        assert_eq!(caesar.encrypt(boxed), "Mjqqt");
+        assert_eq!(caesar.encrypt(cow), "Mjqqt");
+        assert_eq!(caesar.decrypt(Cow::from(owned)), "Czggj");
+    }
+
+    #[test]
+    fn lines_roundtrip() {
+        let key: u8 = 12;
+        let caesar = Caesar::new(key);
+
+        let input = "first line\r\nsecond line\n\nlast";
+        let encrypted: Vec<String> = caesar
+            .encrypt_lines(input.as_bytes())
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(encrypted.len(), 4);
+        assert_eq!(encrypted[2], "");
+
+        let joined = encrypted.join("\n");
+        let decrypted: Vec<String> = caesar
+            .decrypt_lines(joined.as_bytes())
+            .collect::<io::Result<_>>()
+            .unwrap();
+
+        assert_eq!(decrypted, ["first line", "second line", "", "last"]);
+    }
+
+    #[test]
+    fn passphrase_is_stable() {
+        assert_eq!(Caesar::from_passphrase("open sesame").shift(), 7);
+        assert_eq!(Caesar::from_passphrase("").shift(), 12);
+        assert_eq!(
+            Caesar::from_passphrase(String::from("correct horse battery staple")).shift(),
+            23
+        );
+    }
+
+    #[test]
+    fn batch_roundtrip() {
+        let key: u8 = 19;
+        let caesar = Caesar::new(key);
+
+        let records = vec![String::from("alice"), String::from("Bob"), String::new()];
+        let encrypted = caesar.encrypt_all(&records);
+
+        assert_eq!(encrypted.len(), records.len());
+        assert_eq!(encrypted[1], caesar.encrypt("Bob"));
+        assert_eq!(caesar.decrypt_all(encrypted), records);
+    }
+
+    #[test]
+    fn wide_and_negative_shifts() {
+        assert_eq!(Caesar::new(300u32), Caesar::new(14));
+        assert_eq!(Caesar::new(300i64), Caesar::new(14));
+        assert_eq!(Caesar::new(-1), Caesar::new(25));
+        assert_eq!(Caesar::new(-27i8), Caesar::new(25));
+        assert_eq!(Caesar::new(26), Caesar::new(0));
+        assert_eq!(Caesar::new(u64::MAX).shift(), (u64::MAX % 26) as u8);
+        assert_eq!(Caesar::new(u128::MAX).shift(), 21);
+        assert_eq!(
+            Caesar::new(i128::MAX as u128 + 1).shift(),
+            ((i128::MAX as u128 + 1) % 26) as u8
+        );
+        assert_eq!(
+            Caesar::new(i128::MIN).shift(),
+            i128::MIN.rem_euclid(26) as u8
+        );
+    }
+
+    #[test]
+    fn latin1_bytes() {
+        let caesar = Caesar::new(3);
+        // "Café señor" in Latin-1, which isn't valid UTF-8
+        let latin1 = b"Caf\xe9 se\xf1or".to_vec();
+        let mut bytes = latin1.clone();
+
+        caesar.encrypt_bytes(&mut bytes);
+        assert_eq!(bytes, b"Fdi\xe9 vh\xf1ru");
+
+        caesar.decrypt_bytes(&mut bytes);
+        assert_eq!(bytes, latin1);
+    }
 }
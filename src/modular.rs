@@ -0,0 +1,86 @@
+use num::{PrimInt, Unsigned};
+
+/// Shifts every value in `buf` forward by `shift`, modulo `base`. This is
+/// the Caesar transform generalized to any symbol set that can be numbered
+/// `0..base`, like token IDs or base-N digits. Values that are already
+/// `base` or larger are reduced first.
+///
+/// # Panics
+///
+/// Panics if `base` is zero.
+///
+/// # Example
+///
+/// ```
+/// use csr::shift_mod;
+///
+/// // base 36 digits
+/// let mut digits = [0u32, 9, 35];
+/// shift_mod(&mut digits, 3, 36);
+/// assert_eq!(digits, [3, 12, 2]);
+/// ```
+pub fn shift_mod<T: PrimInt + Unsigned>(buf: &mut [T], shift: T, base: T) {
+    let shift = shift % base;
+
+    for x in buf {
+        let value = *x % base;
+
+        // written this way so that it can never overflow, even when base
+        // is close to the maximum value of T
+        *x = if value >= base - shift {
+            value - (base - shift)
+        } else {
+            value + shift
+        };
+    }
+}
+
+/// Shifts every value in `buf` backward by `shift`, modulo `base`, undoing
+/// [`shift_mod`].
+///
+/// # Panics
+///
+/// Panics if `base` is zero.
+///
+/// # Example
+///
+/// ```
+/// use csr::unshift_mod;
+///
+/// let mut digits = [3u32, 12, 2];
+/// unshift_mod(&mut digits, 3, 36);
+/// assert_eq!(digits, [0, 9, 35]);
+/// ```
+pub fn unshift_mod<T: PrimInt + Unsigned>(buf: &mut [T], shift: T, base: T) {
+    shift_mod(buf, base - shift % base, base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_overflow_near_max() {
+        let mut values = [u8::MAX - 1, 0, 100];
+        shift_mod(&mut values, 200, u8::MAX);
+
+        assert_eq!(values, [199, 200, 45]);
+
+        unshift_mod(&mut values, 200, u8::MAX);
+
+        assert_eq!(values, [u8::MAX - 1, 0, 100]);
+    }
+
+    #[test]
+    fn matches_caesar() {
+        use crate::Caesar;
+
+        let input = "thequickbrownfoxjumpsoverthelazydog";
+        let mut values: Vec<u16> = input.bytes().map(|b| u16::from(b - b'a')).collect();
+        shift_mod(&mut values, 29, 26);
+
+        let output: String = values.iter().map(|&v| (v as u8 + b'a') as char).collect();
+
+        assert_eq!(output, Caesar::new(3).encrypt(input));
+    }
+}
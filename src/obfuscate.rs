@@ -0,0 +1,41 @@
+use crate::Caesar;
+
+/// Types whose text can be lightly obfuscated in place, like records
+/// about to be written to disk.
+///
+/// With the `derive` feature enabled, this can be derived for structs with
+/// `#[derive(CaesarObfuscate)]`, which encrypts every `String` field
+/// except those marked `#[csr(skip)]`.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Caesar, Obfuscate};
+///
+/// struct Note {
+///     title: String,
+///     id: u32,
+/// }
+///
+/// impl Obfuscate for Note {
+///     fn obfuscate(&mut self, caesar: &Caesar) {
+///         caesar.encrypt_in_place(&mut self.title);
+///     }
+///
+///     fn deobfuscate(&mut self, caesar: &Caesar) {
+///         caesar.decrypt_in_place(&mut self.title);
+///     }
+/// }
+///
+/// let mut note = Note { title: "Groceries".into(), id: 1 };
+/// note.obfuscate(&Caesar::new(2));
+/// assert_eq!(note.title, "Itqegtkgu");
+/// ```
+pub trait Obfuscate {
+    /// Encrypts the value's text in place.
+    fn obfuscate(&mut self, caesar: &Caesar);
+
+    /// Decrypts the value's text in place, undoing
+    /// [`obfuscate`](Obfuscate::obfuscate).
+    fn deobfuscate(&mut self, caesar: &Caesar);
+}
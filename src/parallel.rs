@@ -0,0 +1,96 @@
+use crate::Caesar;
+use std::thread;
+
+/// A reasonable threshold for the parallel byte APIs. Below a few megabytes
+/// the cost of spawning threads outweighs the work.
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 4 * 1024 * 1024;
+
+impl Caesar {
+    /// Encrypts a mutable slice of bytes in place, splitting it across all
+    /// available cores when it is at least `threshold` bytes long. Shorter
+    /// slices are encrypted on the current thread, exactly like
+    /// [`encrypt_bytes`](Caesar::encrypt_bytes).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::{Caesar, DEFAULT_PARALLEL_THRESHOLD};
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut bytes = b"abcd".repeat(2 * 1024 * 1024);
+    /// c.encrypt_bytes_parallel(&mut bytes, DEFAULT_PARALLEL_THRESHOLD);
+    /// assert_eq!(&bytes[..4], b"cdef");
+    /// ```
+    pub fn encrypt_bytes_parallel(&self, chars: &mut [u8], threshold: usize) {
+        split(chars, threshold, |chunk| self.encrypt_bytes(chunk))
+    }
+
+    /// Decrypts a mutable slice of bytes in place, splitting it across all
+    /// available cores when it is at least `threshold` bytes long. Shorter
+    /// slices are decrypted on the current thread, exactly like
+    /// [`decrypt_bytes`](Caesar::decrypt_bytes).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::{Caesar, DEFAULT_PARALLEL_THRESHOLD};
+    ///
+    /// let c = Caesar::new(2);
+    /// let mut bytes = b"cdef".repeat(2 * 1024 * 1024);
+    /// c.decrypt_bytes_parallel(&mut bytes, DEFAULT_PARALLEL_THRESHOLD);
+    /// assert_eq!(&bytes[..4], b"abcd");
+    /// ```
+    pub fn decrypt_bytes_parallel(&self, chars: &mut [u8], threshold: usize) {
+        split(chars, threshold, |chunk| self.decrypt_bytes(chunk))
+    }
+}
+
+// runs f over roughly equal chunks of chars, one per core. this is only
+// correct because every byte is transformed independently.
+fn split<F: Fn(&mut [u8]) + Sync>(chars: &mut [u8], threshold: usize, f: F) {
+    // checked first, since querying parallelism can cost a syscall or a
+    // cgroup read, which would dwarf the work on short slices
+    if chars.len() < threshold {
+        return f(chars);
+    }
+
+    let threads = thread::available_parallelism().map_or(1, |n| n.get());
+    if threads == 1 {
+        return f(chars);
+    }
+
+    let chunk = chars.len().div_ceil(threads).max(1);
+
+    thread::scope(|scope| {
+        for part in chars.chunks_mut(chunk) {
+            let f = &f;
+            scope.spawn(move || f(part));
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_sequential() {
+        let caesar = Caesar::new(19);
+        let input: Vec<u8> = (0..=255).cycle().take(100_003).collect();
+
+        let mut sequential = input.clone();
+        caesar.encrypt_bytes(&mut sequential);
+
+        // a threshold of zero forces the parallel path even for small input
+        let mut parallel = input.clone();
+        caesar.encrypt_bytes_parallel(&mut parallel, 0);
+
+        assert_eq!(parallel, sequential);
+
+        caesar.decrypt_bytes_parallel(&mut parallel, 0);
+
+        assert_eq!(parallel, input);
+
+        caesar.encrypt_bytes_parallel(&mut [], 0);
+    }
+}
@@ -0,0 +1,119 @@
+use crate::Caesar;
+use std::ffi::{OsStr, OsString};
+use std::path::{Component, Path, PathBuf};
+
+impl Caesar {
+    /// Encrypts an OS string if it is valid UTF-8, and returns it
+    /// unchanged otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    /// use std::ffi::OsStr;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert_eq!(c.encrypt_os_str(OsStr::new("notes.txt")), "pqvgu.vzv");
+    /// ```
+    pub fn encrypt_os_str(&self, s: &OsStr) -> OsString {
+        match s.to_str() {
+            Some(s) => self.encrypt(s).into(),
+            None => s.to_owned(),
+        }
+    }
+
+    /// Decrypts an OS string if it is valid UTF-8, and returns it
+    /// unchanged otherwise.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    /// use std::ffi::OsStr;
+    ///
+    /// let c = Caesar::new(2);
+    /// assert_eq!(c.decrypt_os_str(OsStr::new("pqvgu.vzv")), "notes.txt");
+    /// ```
+    pub fn decrypt_os_str(&self, s: &OsStr) -> OsString {
+        match s.to_str() {
+            Some(s) => self.decrypt(s).into(),
+            None => s.to_owned(),
+        }
+    }
+
+    /// Encrypts each file or directory name in a path. Names that aren't
+    /// valid UTF-8 are passed through unchanged, as are roots, prefixes
+    /// (like `C:`), and `.`/`..`. The path is rebuilt from its
+    /// [`components`](Path::components), so redundant separators are not
+    /// kept.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    /// use std::path::Path;
+    ///
+    /// let c = Caesar::new(2);
+    /// let path = c.encrypt_path(Path::new("../data/notes.txt"));
+    /// assert_eq!(path, Path::new("../fcvc/pqvgu.vzv"));
+    /// ```
+    pub fn encrypt_path(&self, path: &Path) -> PathBuf {
+        map_names(path, |name| self.encrypt_os_str(name))
+    }
+
+    /// Decrypts each file or directory name in a path, the same way
+    /// [`encrypt_path`](Caesar::encrypt_path) encrypts them.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::Caesar;
+    /// use std::path::Path;
+    ///
+    /// let c = Caesar::new(2);
+    /// let path = c.decrypt_path(Path::new("../fcvc/pqvgu.vzv"));
+    /// assert_eq!(path, Path::new("../data/notes.txt"));
+    /// ```
+    pub fn decrypt_path(&self, path: &Path) -> PathBuf {
+        map_names(path, |name| self.decrypt_os_str(name))
+    }
+}
+
+// rebuilds a path, transforming only its normal components
+fn map_names<F: Fn(&OsStr) -> OsString>(path: &Path, f: F) -> PathBuf {
+    path.components()
+        .map(|component| match component {
+            Component::Normal(name) => f(name),
+            other => other.as_os_str().to_owned(),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn absolute_roundtrip() {
+        let caesar = Caesar::new(13);
+        let path = Path::new("/home/user/Secret Plans/v2.md");
+
+        let encrypted = caesar.encrypt_path(path);
+
+        assert_eq!(encrypted, Path::new("/ubzr/hfre/Frperg Cynaf/i2.zq"));
+        assert_eq!(caesar.decrypt_path(&encrypted), path);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn non_utf8_passthrough() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let caesar = Caesar::new(13);
+        let invalid = OsStr::from_bytes(b"caf\xe9");
+        let path = Path::new("dir").join(invalid);
+
+        assert_eq!(caesar.encrypt_os_str(invalid), invalid);
+        assert_eq!(caesar.encrypt_path(&path), Path::new("qve").join(invalid));
+    }
+}
@@ -0,0 +1,104 @@
+use crate::Cipher;
+
+/// Composes multiple ciphers into one. Encrypting applies each stage in
+/// the order they were added, and decrypting undoes them in reverse.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Caesar, Cipher, Pipeline};
+///
+/// let p = Pipeline::new().then(Caesar::new(3)).then(Caesar::new(5));
+/// assert_eq!(p.encrypt("abc"), "ijk");
+/// assert_eq!(p.decrypt("ijk"), "abc");
+/// ```
+#[derive(Default)]
+pub struct Pipeline {
+    stages: Vec<Box<dyn Cipher>>,
+}
+
+impl Pipeline {
+    /// Constructs an empty pipeline, which leaves input unchanged.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a stage to the end of the pipeline and returns it, for
+    /// chaining.
+    pub fn then<C: Cipher + 'static>(mut self, cipher: C) -> Self {
+        self.push(cipher);
+        self
+    }
+
+    /// Adds a stage to the end of the pipeline.
+    pub fn push<C: Cipher + 'static>(&mut self, cipher: C) {
+        self.stages.push(Box::new(cipher));
+    }
+
+    /// Returns the number of stages in the pipeline.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns true if the pipeline has no stages.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+}
+
+impl Cipher for Pipeline {
+    fn encrypt(&self, buf: &str) -> String {
+        self.stages
+            .iter()
+            .fold(buf.to_owned(), |acc, stage| stage.encrypt(&acc))
+    }
+
+    fn decrypt(&self, buf: &str) -> String {
+        self.stages
+            .iter()
+            .rev()
+            .fold(buf.to_owned(), |acc, stage| stage.decrypt(&acc))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Caesar;
+
+    // reverses its input, so stage order is observable
+    struct Reverse;
+
+    impl Cipher for Reverse {
+        fn encrypt(&self, buf: &str) -> String {
+            buf.chars().rev().collect()
+        }
+
+        fn decrypt(&self, buf: &str) -> String {
+            buf.chars().rev().collect()
+        }
+    }
+
+    #[test]
+    fn empty_is_identity() {
+        let pipeline = Pipeline::new();
+
+        assert!(pipeline.is_empty());
+        assert_eq!(pipeline.encrypt("Hello"), "Hello");
+    }
+
+    #[test]
+    fn roundtrip() {
+        let pipeline = Pipeline::new()
+            .then(Caesar::new(3))
+            .then(Reverse)
+            .then(Caesar::new(10));
+
+        let input = "Attack at dawn!";
+        let encrypted = pipeline.encrypt(input);
+
+        assert_eq!(pipeline.len(), 3);
+        assert_eq!(encrypted, "!ajnq gn xpnggN");
+        assert_eq!(pipeline.decrypt(&encrypted), input);
+    }
+}
@@ -0,0 +1,308 @@
+use crate::crack::letter_counts;
+use crate::{crack, Caesar};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+// how many of each n-gram size a report keeps
+const TOP_NGRAMS: usize = 10;
+
+// below this many letters, statistics say too little to guess from
+const MIN_LETTERS: u64 = 20;
+
+// English sits around 0.066 and uniformly random letters at 1/26 ~ 0.038;
+// substitution ciphers keep the plaintext's value, polyalphabetic ones
+// flatten it towards random
+const MONOALPHABETIC_IOC: f64 = 0.055;
+
+/// A kind of cipher a text might have been produced with, from
+/// [`Report::guesses`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Guess {
+    /// The text already reads like English.
+    Plaintext,
+    /// A Caesar shift, with the key that decrypts it.
+    Caesar(Caesar),
+    /// Some other one-to-one letter substitution.
+    Substitution,
+    /// A cipher that shifts letters by varying amounts, such as Vigenère
+    /// or a [`Rotating`](crate::Rotating) keystream.
+    Polyalphabetic,
+}
+
+impl Guess {
+    fn name(&self) -> &'static str {
+        match self {
+            Guess::Plaintext => "plaintext",
+            Guess::Caesar(_) => "caesar",
+            Guess::Substitution => "substitution",
+            Guess::Polyalphabetic => "polyalphabetic",
+        }
+    }
+}
+
+/// Statistics about a text, for telling plaintext from ciphertext and
+/// judging what kind of cipher produced it. Letters are counted
+/// case-insensitively and anything that isn't an ASCII letter is ignored,
+/// except by `length`.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Caesar, Guess, Report};
+///
+/// let ciphertext = Caesar::new(4).encrypt("It was the best of times, it was the worst of times");
+/// let report = Report::new(&ciphertext);
+///
+/// assert_eq!(report.letters, 39);
+/// assert_eq!(report.guesses, [Guess::Caesar(Caesar::new(4))]);
+/// assert!(report.to_json().starts_with("{\"length\":51,\"letters\":39,"));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// The length of the text in characters.
+    pub length: usize,
+    /// How many ASCII letters the text contains.
+    pub letters: u64,
+    /// How many times each letter, a to z, occurs.
+    pub frequencies: [u64; 26],
+    /// The chance that two letters picked at random are the same, around
+    /// 0.066 for English and 0.038 for random letters.
+    pub index_of_coincidence: f64,
+    /// The Shannon entropy of the letters in bits, at most log2(26) ~ 4.7.
+    pub entropy: f64,
+    /// The most common letter pairs, most common first, counted across
+    /// word boundaries.
+    pub bigrams: Vec<(String, u64)>,
+    /// The most common letter triples, counted the same way.
+    pub trigrams: Vec<(String, u64)>,
+    /// What produced the text, most likely first. Empty if the text is
+    /// too short to tell.
+    pub guesses: Vec<Guess>,
+}
+
+impl Report {
+    /// Analyzes a text.
+    pub fn new<S: AsRef<str>>(text: S) -> Self {
+        let text = text.as_ref();
+        let frequencies = letter_counts(text);
+        let letters = frequencies.iter().sum();
+
+        let stripped: Vec<u8> = text
+            .bytes()
+            .filter(u8::is_ascii_alphabetic)
+            .map(|c| c.to_ascii_lowercase())
+            .collect();
+
+        let mut report = Report {
+            length: text.chars().count(),
+            letters,
+            frequencies,
+            index_of_coincidence: index_of_coincidence(&frequencies),
+            entropy: entropy(&frequencies),
+            bigrams: top_ngrams(&stripped, 2),
+            trigrams: top_ngrams(&stripped, 3),
+            guesses: Vec::new(),
+        };
+
+        report.guesses = report.guess(text);
+        report
+    }
+
+    fn guess(&self, text: &str) -> Vec<Guess> {
+        if self.letters < MIN_LETTERS {
+            return Vec::new();
+        }
+
+        let mut guesses = Vec::new();
+
+        // a shift that scores far better than every other one is a shift
+        // that was actually used
+        let candidates = crack(text);
+        if candidates[0].score * 2.0 < candidates[1].score {
+            guesses.push(match candidates[0].caesar.shift() {
+                0 => Guess::Plaintext,
+                _ => Guess::Caesar(candidates[0].caesar),
+            });
+        }
+
+        if self.index_of_coincidence < MONOALPHABETIC_IOC {
+            guesses.push(Guess::Polyalphabetic);
+        } else if guesses.is_empty() {
+            guesses.push(Guess::Substitution);
+        }
+
+        guesses
+    }
+
+    /// Formats the report as a JSON object, with a field for each of the
+    /// struct's. Frequencies are an object keyed by letter, n-grams are
+    /// `[ngram, count]` pairs, and guesses are objects with a `cipher`
+    /// name and, for Caesar, the `shift`.
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+
+        // writing to a String can't fail
+        let _ = write!(
+            json,
+            "{{\"length\":{},\"letters\":{},\"frequencies\":{{",
+            self.length, self.letters
+        );
+
+        for (i, count) in self.frequencies.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = write!(
+                json,
+                "{}\"{}\":{}",
+                separator,
+                (b'a' + i as u8) as char,
+                count
+            );
+        }
+
+        let _ = write!(
+            json,
+            "}},\"index_of_coincidence\":{},\"entropy\":{},\"bigrams\":{},\"trigrams\":{},\"guesses\":[",
+            self.index_of_coincidence,
+            self.entropy,
+            ngrams_json(&self.bigrams),
+            ngrams_json(&self.trigrams)
+        );
+
+        for (i, guess) in self.guesses.iter().enumerate() {
+            let separator = if i == 0 { "" } else { "," };
+            let _ = match guess {
+                Guess::Caesar(caesar) => write!(
+                    json,
+                    "{}{{\"cipher\":\"{}\",\"shift\":{}}}",
+                    separator,
+                    guess.name(),
+                    caesar.shift()
+                ),
+                _ => write!(json, "{}{{\"cipher\":\"{}\"}}", separator, guess.name()),
+            };
+        }
+
+        json.push_str("]}");
+        json
+    }
+}
+
+fn index_of_coincidence(counts: &[u64; 26]) -> f64 {
+    let total: u64 = counts.iter().sum();
+
+    if total < 2 {
+        return 0.0;
+    }
+
+    let pairs: u64 = counts.iter().map(|&n| n * n.saturating_sub(1)).sum();
+    pairs as f64 / (total * (total - 1)) as f64
+}
+
+fn entropy(counts: &[u64; 26]) -> f64 {
+    let total = counts.iter().sum::<u64>() as f64;
+
+    counts
+        .iter()
+        .filter(|&&n| n > 0)
+        .map(|&n| {
+            let p = n as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+// ties are broken alphabetically, so reports are reproducible
+fn top_ngrams(letters: &[u8], n: usize) -> Vec<(String, u64)> {
+    let mut counts: BTreeMap<&[u8], u64> = BTreeMap::new();
+
+    for ngram in letters.windows(n) {
+        *counts.entry(ngram).or_insert(0) += 1;
+    }
+
+    let mut ngrams: Vec<(String, u64)> = counts
+        .into_iter()
+        .map(|(ngram, count)| (String::from_utf8_lossy(ngram).into_owned(), count))
+        .collect();
+
+    // stable, so equal counts stay in alphabetical order
+    ngrams.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+    ngrams.truncate(TOP_NGRAMS);
+    ngrams
+}
+
+// n-grams are only ever lowercase letters, so they need no escaping
+fn ngrams_json(ngrams: &[(String, u64)]) -> String {
+    let entries: Vec<String> = ngrams
+        .iter()
+        .map(|(ngram, count)| format!("[\"{}\",{}]", ngram, count))
+        .collect();
+
+    format!("[{}]", entries.join(","))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ENGLISH: &str = "It is a truth universally acknowledged, that a single man in \
+        possession of a good fortune, must be in want of a wife. However little known the \
+        feelings or views of such a man may be on his first entering a neighbourhood, this \
+        truth is so well fixed in the minds of the surrounding families, that he is \
+        considered the rightful property of some one or other of their daughters.";
+
+    #[test]
+    fn statistics() {
+        let report = Report::new("abca bb");
+
+        assert_eq!(report.length, 7);
+        assert_eq!(report.letters, 6);
+        assert_eq!(report.frequencies[..3], [2, 3, 1]);
+        assert_eq!(report.bigrams[0], ("ab".to_string(), 2));
+        assert_eq!(report.trigrams.len(), 4);
+        // (2 * 1 + 3 * 2) / (6 * 5)
+        assert!((report.index_of_coincidence - 8.0 / 30.0).abs() < 1e-12);
+        assert!((Report::new("abcd").entropy - 2.0).abs() < 1e-12);
+        assert!(report.guesses.is_empty());
+    }
+
+    #[test]
+    fn guesses() {
+        assert_eq!(Report::new(ENGLISH).guesses, [Guess::Plaintext]);
+
+        let caesar = Caesar::new(17);
+        assert_eq!(
+            Report::new(caesar.encrypt(ENGLISH)).guesses,
+            [Guess::Caesar(caesar)]
+        );
+
+        // a keyed alphabet keeps English's letter distribution, but no
+        // single shift undoes it
+        let keyed = b"qwertyuiopasdfghjklzxcvbnm";
+        let substituted: String = ENGLISH
+            .bytes()
+            .map(|c| match c {
+                b'a'..=b'z' => keyed[(c - b'a') as usize] as char,
+                _ => c as char,
+            })
+            .collect();
+        assert_eq!(Report::new(substituted).guesses, [Guess::Substitution]);
+
+        // shifting each letter by its position flattens the distribution
+        let mut rotating = crate::Rotating::increment(Caesar::new(0), 1, 7);
+        let mut bytes = ENGLISH.as_bytes().to_vec();
+        crate::keystream::encrypt_bytes(&mut rotating, &mut bytes);
+        let report = Report::new(String::from_utf8(bytes).unwrap());
+        assert_eq!(report.guesses, [Guess::Polyalphabetic]);
+    }
+
+    #[test]
+    fn json() {
+        let json = Report::new("Hello, hello!").to_json();
+
+        assert!(json.starts_with("{\"length\":13,\"letters\":10,\"frequencies\":{\"a\":0,"));
+        assert!(
+            json.contains("\"bigrams\":[[\"el\",2],[\"he\",2],[\"ll\",2],[\"lo\",2],[\"oh\",1]]")
+        );
+        assert!(json.ends_with(",\"guesses\":[]}"));
+    }
+}
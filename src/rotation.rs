@@ -0,0 +1,180 @@
+use crate::keystream::{self, Keystream};
+use crate::{Caesar, Cipher};
+
+/// A Caesar whose key changes every `n` letters according to a schedule,
+/// like several historical field ciphers. Non-letters don't count towards
+/// `n`, since they are never shifted.
+///
+/// `Rotating` is a [`Keystream`], so it can drive a
+/// [`StreamCipher`](crate::keystream::StreamCipher) for chunked input. It
+/// also implements [`Cipher`], which always starts from the first key.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Caesar, Cipher, Rotating};
+///
+/// // shift by 1 for two letters, then by 2, then by 3...
+/// let r = Rotating::increment(Caesar::new(1), 2, 1);
+/// assert_eq!(r.encrypt("aa aa aa"), "bb cc dd");
+/// assert_eq!(r.decrypt("bb cc dd"), "aa aa aa");
+/// ```
+pub struct Rotating {
+    every: usize,
+    schedule: Box<dyn Fn(usize) -> Caesar>,
+    letters: usize,
+}
+
+impl Rotating {
+    /// Constructs a schedule that starts at `start` and adds `step` to the
+    /// shift every `every` letters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    pub fn increment(start: Caesar, every: usize, step: u8) -> Self {
+        let step = Caesar::new(step);
+
+        Rotating::with(every, move |block| {
+            Caesar::new(start.shift as usize + block % 26 * step.shift as usize)
+        })
+    }
+
+    /// Constructs a schedule that cycles through `keys`, moving to the next
+    /// one every `every` letters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero or `keys` is empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::{Caesar, Cipher, Rotating};
+    ///
+    /// let r = Rotating::keys(vec![Caesar::new(3), Caesar::new(0)], 1);
+    /// assert_eq!(r.encrypt("aaaa"), "dada");
+    /// ```
+    pub fn keys(keys: Vec<Caesar>, every: usize) -> Self {
+        assert!(!keys.is_empty(), "key schedule must not be empty");
+
+        Rotating::with(every, move |block| keys[block % keys.len()])
+    }
+
+    /// Constructs a schedule from a callback, which is given the index of
+    /// each block of `every` letters (0 for the first block) and returns
+    /// the key to use for it. The callback must be deterministic for
+    /// decryption to work.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `every` is zero.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use csr::{Caesar, Cipher, Rotating};
+    ///
+    /// let r = Rotating::with(3, |block| Caesar::new(block * block));
+    /// assert_eq!(r.encrypt("aaaaaaaaa"), "aaabbbeee");
+    /// ```
+    pub fn with<F: Fn(usize) -> Caesar + 'static>(every: usize, schedule: F) -> Self {
+        assert!(every > 0, "keys must rotate after at least one letter");
+
+        Rotating {
+            every,
+            schedule: Box::new(schedule),
+            letters: 0,
+        }
+    }
+
+    /// Moves back to the start of the schedule, as if no letters had been
+    /// processed yet.
+    pub fn reset(&mut self) {
+        self.letters = 0;
+    }
+
+    // the shift to apply to the nth letter
+    fn shift_at(&self, letter: usize) -> u8 {
+        (self.schedule)(letter / self.every).shift
+    }
+}
+
+impl Keystream for Rotating {
+    fn next_shift(&mut self) -> u8 {
+        let shift = self.shift_at(self.letters);
+        self.letters += 1;
+        shift
+    }
+}
+
+// a fresh position in a schedule, so Cipher can take &self
+struct Fresh<'a> {
+    rotating: &'a Rotating,
+    letters: usize,
+}
+
+impl Keystream for Fresh<'_> {
+    fn next_shift(&mut self) -> u8 {
+        let shift = self.rotating.shift_at(self.letters);
+        self.letters += 1;
+        shift
+    }
+}
+
+impl Cipher for Rotating {
+    fn encrypt(&self, buf: &str) -> String {
+        let mut vec = buf.as_bytes().to_vec();
+        let mut keystream = Fresh {
+            rotating: self,
+            letters: 0,
+        };
+        keystream::encrypt_bytes(&mut keystream, &mut vec);
+
+        String::from_utf8(vec).expect("output is valid UTF-8")
+    }
+
+    fn decrypt(&self, buf: &str) -> String {
+        let mut vec = buf.as_bytes().to_vec();
+        let mut keystream = Fresh {
+            rotating: self,
+            letters: 0,
+        };
+        keystream::decrypt_bytes(&mut keystream, &mut vec);
+
+        String::from_utf8(vec).expect("output is valid UTF-8")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::keystream::StreamCipher;
+
+    #[test]
+    fn increment_wraps() {
+        let r = Rotating::increment(Caesar::new(25), 1, 1);
+
+        assert_eq!(r.encrypt("aaa"), "zab");
+    }
+
+    #[test]
+    fn stream_matches_cipher() {
+        let mut r = Rotating::keys(vec![Caesar::new(5), Caesar::new(11), Caesar::new(2)], 4);
+        let input = "Rotating keys model historical field ciphers.";
+        let expected = r.encrypt(input);
+
+        let mut bytes = input.as_bytes().to_vec();
+        let mut stream = StreamCipher::encryptor(&mut r);
+        for chunk in bytes.chunks_mut(7) {
+            stream.update(chunk);
+        }
+
+        assert_eq!(bytes, expected.as_bytes());
+
+        r.reset();
+        keystream::decrypt_bytes(&mut r, &mut bytes);
+
+        assert_eq!(bytes, input.as_bytes());
+    }
+}
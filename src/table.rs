@@ -0,0 +1,165 @@
+use crate::{Caesar, Cipher};
+
+/// Returns the tabula recta: row `n` is the ciphertext alphabet for a
+/// shift of `n`, so `tabula_recta()[key][plain]` is the encrypted letter.
+/// This is the table polyalphabetic ciphers such as Vigenère read from.
+///
+/// # Example
+///
+/// ```
+/// use csr::tabula_recta;
+///
+/// let table = tabula_recta();
+/// assert_eq!(table[0][0], 'a');
+/// assert_eq!(table[2][0], 'c');
+/// assert_eq!(table[25][1], 'a');
+/// ```
+pub fn tabula_recta() -> [[char; 26]; 26] {
+    let mut table = [['a'; 26]; 26];
+
+    for (shift, row) in table.iter_mut().enumerate() {
+        *row = Caesar::new(shift as u8).mapping();
+    }
+
+    table
+}
+
+/// A Caesar precomputed into two 256-byte translation tables, one for each
+/// direction, so transforming is a single index per byte.
+///
+/// This is not the fast path for bulk data. [`Caesar::encrypt_bytes`] uses
+/// branchless arithmetic that the compiler vectorizes, and runs about twice
+/// as fast as the per-byte lookups here. Use a table when a by-value byte
+/// mapping is wanted, such as for feeding another table-driven transform.
+///
+/// # Example
+///
+/// ```
+/// use csr::{Caesar, CaesarTable};
+///
+/// let table = CaesarTable::new(Caesar::new(2));
+/// let mut bytes = *b"Attack at dawn!";
+/// table.encrypt_bytes(&mut bytes);
+/// assert_eq!(&bytes, b"Cvvcem cv fcyp!");
+/// ```
+#[derive(Clone)]
+pub struct CaesarTable {
+    caesar: Caesar,
+    encrypt: [u8; 256],
+    decrypt: [u8; 256],
+}
+
+impl CaesarTable {
+    /// Builds the translation tables for a Caesar.
+    pub fn new(caesar: Caesar) -> Self {
+        let mut encrypt = [0; 256];
+        let mut decrypt = [0; 256];
+
+        for (i, (e, d)) in encrypt.iter_mut().zip(decrypt.iter_mut()).enumerate() {
+            *e = i as u8;
+            *d = i as u8;
+        }
+
+        caesar.encrypt_bytes(&mut encrypt);
+        caesar.decrypt_bytes(&mut decrypt);
+
+        CaesarTable {
+            caesar,
+            encrypt,
+            decrypt,
+        }
+    }
+
+    /// Returns the Caesar these tables were built from.
+    pub fn caesar(&self) -> Caesar {
+        self.caesar
+    }
+
+    /// Encrypts a buffer.
+    pub fn encrypt<S: AsRef<str>>(&self, buf: S) -> String {
+        let mut vec = buf.as_ref().as_bytes().to_vec();
+        self.encrypt_bytes(&mut vec);
+
+        // the tables only map ASCII letters to other ASCII letters
+        String::from_utf8(vec).expect("output is valid UTF-8")
+    }
+
+    /// Decrypts a buffer.
+    pub fn decrypt<S: AsRef<str>>(&self, buf: S) -> String {
+        let mut vec = buf.as_ref().as_bytes().to_vec();
+        self.decrypt_bytes(&mut vec);
+
+        // the tables only map ASCII letters to other ASCII letters
+        String::from_utf8(vec).expect("output is valid UTF-8")
+    }
+
+    /// Encrypts a mutable slice of bytes in place.
+    pub fn encrypt_bytes(&self, chars: &mut [u8]) {
+        for c in chars {
+            *c = self.encrypt[*c as usize];
+        }
+    }
+
+    /// Decrypts a mutable slice of bytes in place.
+    pub fn decrypt_bytes(&self, chars: &mut [u8]) {
+        for c in chars {
+            *c = self.decrypt[*c as usize];
+        }
+    }
+}
+
+impl From<Caesar> for CaesarTable {
+    fn from(caesar: Caesar) -> Self {
+        CaesarTable::new(caesar)
+    }
+}
+
+impl Cipher for CaesarTable {
+    fn encrypt(&self, buf: &str) -> String {
+        CaesarTable::encrypt(self, buf)
+    }
+
+    fn decrypt(&self, buf: &str) -> String {
+        CaesarTable::decrypt(self, buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_are_rotations() {
+        let table = tabula_recta();
+
+        for (shift, row) in table.iter().enumerate() {
+            for (plain, &c) in row.iter().enumerate() {
+                assert_eq!(c, table[0][(plain + shift) % 26]);
+            }
+        }
+    }
+
+    #[test]
+    fn table_matches_caesar() {
+        let all: Vec<u8> = (0..=255).collect();
+
+        for shift in 0..26 {
+            let caesar = Caesar::new(shift);
+            let table = CaesarTable::from(caesar);
+
+            let (mut expected, mut actual) = (all.clone(), all.clone());
+            caesar.encrypt_bytes(&mut expected);
+            table.encrypt_bytes(&mut actual);
+
+            assert_eq!(actual, expected);
+
+            table.decrypt_bytes(&mut actual);
+
+            assert_eq!(actual, all);
+            assert_eq!(
+                table.decrypt(table.encrypt("Round trip! 😀")),
+                "Round trip! 😀"
+            );
+        }
+    }
+}
@@ -0,0 +1,316 @@
+use crate::Caesar;
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::path::{Path, PathBuf};
+
+/// Settings for [`Caesar::encrypt_tree`] and [`Caesar::decrypt_tree`].
+///
+/// Globs support `*`, which matches any run of characters other than `/`,
+/// and `?`, which matches one. A glob without a `/` is matched against
+/// each file's name, and one with a `/` against its path relative to the
+/// source directory, using `/` as the separator on every platform.
+#[derive(Debug, Clone, Default)]
+pub struct TreeOptions {
+    names: bool,
+    include: Vec<String>,
+    exclude: Vec<String>,
+    dry_run: bool,
+}
+
+impl TreeOptions {
+    /// Constructs options that transform every file's contents, keep
+    /// names as they are, and write the output.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets whether file and directory names are transformed as well.
+    pub fn names(mut self, names: bool) -> Self {
+        self.names = names;
+        self
+    }
+
+    /// Only transforms files matching this glob. If called more than once,
+    /// files matching any of the globs are included.
+    pub fn include<S: Into<String>>(mut self, glob: S) -> Self {
+        self.include.push(glob.into());
+        self
+    }
+
+    /// Skips files matching this glob, even if they are included.
+    pub fn exclude<S: Into<String>>(mut self, glob: S) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    /// Sets whether to only work out what would be written, touching
+    /// nothing.
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    fn selects(&self, relative: &str) -> bool {
+        let name = relative.rsplit('/').next().unwrap_or(relative);
+        let matches = |glob: &String| {
+            let target = if glob.contains('/') { relative } else { name };
+            glob_match(glob.as_bytes(), target.as_bytes())
+        };
+
+        (self.include.is_empty() || self.include.iter().any(matches))
+            && !self.exclude.iter().any(matches)
+    }
+}
+
+/// One file written, or that would be written on a dry run, by a tree
+/// transformation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeEntry {
+    /// The file that was read.
+    pub src: PathBuf,
+    /// The file that was written.
+    pub dst: PathBuf,
+}
+
+impl Caesar {
+    /// Encrypts every regular file under the directory `src` into the same
+    /// layout under `dst`, creating directories as needed. Symbolic links
+    /// are skipped. Returns the files transformed, in path order.
+    ///
+    /// The whole tree is listed before anything is written, so `dst` may be
+    /// inside `src`, but fails with [`ErrorKind::InvalidInput`] if it is
+    /// `src` itself or if any output would land on a file in the listing.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use csr::{Caesar, TreeOptions};
+    ///
+    /// let options = TreeOptions::new().names(true).include("*.csv").dry_run(true);
+    ///
+    /// for entry in Caesar::new(3).encrypt_tree("data", "obfuscated", &options).unwrap() {
+    ///     println!("{} -> {}", entry.src.display(), entry.dst.display());
+    /// }
+    /// ```
+    pub fn encrypt_tree<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        src: P,
+        dst: Q,
+        options: &TreeOptions,
+    ) -> io::Result<Vec<TreeEntry>> {
+        self.tree(src.as_ref(), dst.as_ref(), options, false)
+    }
+
+    /// Decrypts every regular file under the directory `src` into `dst`,
+    /// the same way [`encrypt_tree`](Caesar::encrypt_tree) encrypts. Globs
+    /// are matched against the names in `src`, so they should be encrypted
+    /// names if names were encrypted.
+    pub fn decrypt_tree<P: AsRef<Path>, Q: AsRef<Path>>(
+        &self,
+        src: P,
+        dst: Q,
+        options: &TreeOptions,
+    ) -> io::Result<Vec<TreeEntry>> {
+        self.tree(src.as_ref(), dst.as_ref(), options, true)
+    }
+
+    fn tree(
+        &self,
+        src: &Path,
+        dst: &Path,
+        options: &TreeOptions,
+        decrypt: bool,
+    ) -> io::Result<Vec<TreeEntry>> {
+        if let (Ok(a), Ok(b)) = (src.canonicalize(), dst.canonicalize()) {
+            if a == b {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "source and destination are the same directory",
+                ));
+            }
+        }
+
+        let mut files = Vec::new();
+        walk(src, Path::new(""), &mut files)?;
+
+        let entries: Vec<TreeEntry> = files
+            .iter()
+            .filter(|relative| options.selects(&slash_path(relative)))
+            .map(|relative| {
+                let renamed = match (options.names, decrypt) {
+                    (false, _) => relative.clone(),
+                    (true, false) => self.encrypt_path(relative),
+                    (true, true) => self.decrypt_path(relative),
+                };
+
+                TreeEntry {
+                    src: src.join(relative),
+                    dst: dst.join(renamed),
+                }
+            })
+            .collect();
+
+        // an output landing on a source that hasn't been read yet would
+        // destroy it, so outputs may not shadow anything in the listing.
+        // if dst doesn't exist yet, nothing in the listing can be under it
+        if let (Ok(src), Ok(dst_root)) = (src.canonicalize(), dst.canonicalize()) {
+            let sources: HashSet<PathBuf> = files.iter().map(|file| src.join(file)).collect();
+            let overlaps = entries.iter().any(|entry| {
+                entry
+                    .dst
+                    .strip_prefix(dst)
+                    .is_ok_and(|relative| sources.contains(&dst_root.join(relative)))
+            });
+
+            if overlaps {
+                return Err(io::Error::new(
+                    ErrorKind::InvalidInput,
+                    "destination would overwrite files in the source",
+                ));
+            }
+        }
+
+        if !options.dry_run {
+            for entry in &entries {
+                if let Some(parent) = entry.dst.parent() {
+                    fs::create_dir_all(parent)?;
+                }
+
+                if decrypt {
+                    self.decrypt_file(&entry.src, &entry.dst)?;
+                } else {
+                    self.encrypt_file(&entry.src, &entry.dst)?;
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+}
+
+// collects the paths of regular files under root, relative to it
+fn walk(root: &Path, relative: &Path, files: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(root.join(relative))?.collect::<io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|entry| entry.file_name());
+
+    for entry in entries {
+        let path = relative.join(entry.file_name());
+        let file_type = entry.file_type()?;
+
+        if file_type.is_dir() {
+            walk(root, &path, files)?;
+        } else if file_type.is_file() {
+            files.push(path);
+        }
+    }
+
+    Ok(())
+}
+
+fn slash_path(path: &Path) -> String {
+    path.iter()
+        .map(|name| name.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn glob_match(glob: &[u8], s: &[u8]) -> bool {
+    match glob {
+        [] => s.is_empty(),
+        [b'*', rest @ ..] => (0..=s.len())
+            .take_while(|&i| i == 0 || s[i - 1] != b'/')
+            .any(|i| glob_match(rest, &s[i..])),
+        [b'?', rest @ ..] => matches!(s, [c, tail @ ..] if *c != b'/' && glob_match(rest, tail)),
+        [c, rest @ ..] => matches!(s, [d, tail @ ..] if c == d && glob_match(rest, tail)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("csr-tree-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn globs() {
+        assert!(glob_match(b"*.csv", b"people.csv"));
+        assert!(glob_match(b"data?.*", b"data1.txt"));
+        assert!(!glob_match(b"*.csv", b"people.csv.bak"));
+        assert!(!glob_match(b"*", b"a/b"));
+        assert!(glob_match(b"raw/*/*.txt", b"raw/2020/notes.txt"));
+
+        let options = TreeOptions::new().include("*.txt").exclude("secret*");
+        assert!(options.selects("a/notes.txt"));
+        assert!(!options.selects("a/secret.txt"));
+        assert!(!options.selects("a/notes.csv"));
+    }
+
+    #[test]
+    fn tree_round_trip() {
+        let caesar = Caesar::new(5);
+        let root = temp_dir("round-trip");
+        let (plain, cipher, back) = (root.join("plain"), root.join("cipher"), root.join("back"));
+
+        fs::create_dir_all(plain.join("notes")).unwrap();
+        fs::write(plain.join("notes/todo.txt"), "Buy milk").unwrap();
+        fs::write(plain.join("notes/skip.bin"), "untouched").unwrap();
+        fs::write(plain.join("readme.txt"), "Hello").unwrap();
+
+        let options = TreeOptions::new().names(true).include("*.txt");
+        let dry = caesar
+            .encrypt_tree(&plain, &cipher, &options.clone().dry_run(true))
+            .unwrap();
+        assert!(!cipher.exists());
+
+        let written = caesar.encrypt_tree(&plain, &cipher, &options).unwrap();
+        assert_eq!(dry, written);
+        assert_eq!(
+            written.iter().map(|e| e.dst.clone()).collect::<Vec<_>>(),
+            [cipher.join("styjx/ytit.ycy"), cipher.join("wjfirj.ycy")]
+        );
+        assert_eq!(
+            fs::read_to_string(cipher.join("wjfirj.ycy")).unwrap(),
+            "Mjqqt"
+        );
+
+        let options = TreeOptions::new().names(true);
+        caesar.decrypt_tree(&cipher, &back, &options).unwrap();
+        assert_eq!(
+            fs::read_to_string(back.join("notes/todo.txt")).unwrap(),
+            "Buy milk"
+        );
+        assert!(!back.join("notes/skip.bin").exists());
+
+        assert_eq!(
+            caesar
+                .encrypt_tree(&plain, &plain, &options)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+
+        // outputs under src that would land on unread sources
+        fs::create_dir_all(plain.join("sub")).unwrap();
+        fs::write(plain.join("a"), "top").unwrap();
+        fs::write(plain.join("sub/a"), "nested").unwrap();
+        assert_eq!(
+            caesar
+                .encrypt_tree(&plain, plain.join("sub"), &TreeOptions::new())
+                .unwrap_err()
+                .kind(),
+            ErrorKind::InvalidInput
+        );
+        assert_eq!(fs::read_to_string(plain.join("sub/a")).unwrap(), "nested");
+        caesar
+            .encrypt_tree(&plain, plain.join("out"), &TreeOptions::new())
+            .unwrap();
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -0,0 +1,60 @@
+#![cfg(feature = "derive")]
+
+use csr::{Caesar, CaesarObfuscate, Obfuscate};
+use std::collections::HashMap;
+
+#[derive(CaesarObfuscate, Debug, PartialEq)]
+pub struct Record {
+    pub name: String,
+    #[csr(skip)]
+    pub id: String,
+    pub(crate) email: std::string::String,
+    age: u32,
+    tags: HashMap<String, Vec<String>>,
+}
+
+#[derive(CaesarObfuscate)]
+struct Pair(String, #[csr(skip)] String);
+
+#[derive(CaesarObfuscate)]
+struct Unit;
+
+fn record() -> Record {
+    Record {
+        name: "Alice".into(),
+        id: "abc-123".into(),
+        email: "alice@example.com".into(),
+        age: 30,
+        tags: HashMap::new(),
+    }
+}
+
+#[test]
+fn named_fields_roundtrip() {
+    let caesar = Caesar::new(3);
+    let mut r = record();
+
+    r.obfuscate(&caesar);
+
+    assert_eq!(r.name, "Dolfh");
+    assert_eq!(r.id, "abc-123");
+    assert_eq!(r.email, "dolfh@hadpsoh.frp");
+    assert_eq!(r.age, 30);
+
+    r.deobfuscate(&caesar);
+
+    assert_eq!(r, record());
+}
+
+#[test]
+fn tuple_and_unit_structs() {
+    let caesar = Caesar::new(1);
+    let mut pair = Pair("abc".into(), "abc".into());
+
+    pair.obfuscate(&caesar);
+
+    assert_eq!(pair.0, "bcd");
+    assert_eq!(pair.1, "abc");
+
+    Unit.obfuscate(&caesar);
+}